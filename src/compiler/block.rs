@@ -0,0 +1,19 @@
+use super::local::Local;
+
+/// Constructs enclosing the statement currently being output, these determine
+/// how control flow statements are lowered into Rust.
+#[derive(Debug, Clone)]
+pub enum Block {
+    /// A Python loop and the label of the Rust `loop` that it was output as
+    Loop(Local),
+    /// The body of a closure passed to `catch_unwind`, control flow leaves it
+    /// by returning a `cannolib::ControlFlow`
+    Closure,
+    /// An except handler and the local holding the exception being handled
+    Handler(Local),
+    /// A function body and the name of its first parameter, `return` can't
+    /// cross this boundary
+    Function(Option<String>),
+    /// A class body, no control flow can cross this boundary
+    Class
+}
@@ -5,14 +5,22 @@ use std::fmt;
 pub enum CompilerError
 {
     IOError(String),
-    ParserError(String)
+    ParserError(String),
+    SyntaxError(String),
+    NotImplementedError(String),
+    ArgumentError(String)
 }
 
 impl fmt::Display for CompilerError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             CompilerError::IOError(ref s) => write!(f, "{}", s),
-            CompilerError::ParserError(ref s) => write!(f, "ParserError: {}", s)
+            CompilerError::ParserError(ref s) => write!(f, "ParserError: {}", s),
+            CompilerError::SyntaxError(ref s) => write!(f, "SyntaxError: {}", s),
+            CompilerError::NotImplementedError(ref s) =>
+                write!(f, "NotImplementedError: {} not supported", s),
+            CompilerError::ArgumentError(ref s) =>
+                write!(f, "ArgumentError: {}", s)
         }
     }
 }
@@ -21,7 +29,10 @@ impl error::Error for CompilerError {
     fn description(&self) -> &str {
         match *self {
             CompilerError::IOError(_) => "i/o error",
-            CompilerError::ParserError(_) => "parser error"
+            CompilerError::ParserError(_) => "parser error",
+            CompilerError::SyntaxError(_) => "syntax error",
+            CompilerError::NotImplementedError(_) => "not implemented",
+            CompilerError::ArgumentError(_) => "argument error"
         }
     }
 }
@@ -1,11 +1,13 @@
 mod util;
 mod errors;
 mod local;
+mod block;
+mod scope;
 
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::sync::Mutex;
-use std::iter::Peekable;
+use std::iter::{Peekable, Zip};
 use std::slice::Iter;
 use std::collections::HashSet;
 use clap::ArgMatches;
@@ -15,6 +17,8 @@ use super::parser;
 use super::parser::ast::*;
 use self::errors::CompilerError;
 use self::local::Local;
+use self::block::Block;
+use self::scope::{Scope, Binding};
 
 const INDENT: &str = "    ";
 
@@ -29,6 +33,13 @@ lazy_static! {
     static ref SRC_ROOT: Mutex<String> = Mutex::new(String::new());
     /// All of the built-in modules provided by Python
     static ref BUILTIN_MODS: HashSet<&'static str> = init_modules();
+    /// Stack of loops and scopes enclosing the statement being output
+    static ref BLOCK_STACK: Mutex<Vec<Block>> = Mutex::new(vec![]);
+    /// Stack of the scopes enclosing the statement being output, the module
+    /// scope isn't included
+    static ref SCOPE_STACK: Mutex<Vec<Scope>> = Mutex::new(vec![]);
+    /// Optimization level given by the `-o` flag, 0 when it isn't present
+    static ref OPT_LEVEL: Mutex<u32> = Mutex::new(0);
 }
 
 fn init_modules() -> HashSet<&'static str> {
@@ -44,6 +55,17 @@ pub fn compile(file: &str, opt_args: Option<&ArgMatches>)
     let (src_root, module) = util::get_file_prefix(file)?;
     *SRC_ROOT.lock().unwrap() = src_root;
 
+    // Set on every call so a previous compilation's level isn't inherited
+    let level = match opt_args.and_then(|args| args.value_of("o")) {
+        Some(level) => match level.parse() {
+            Ok(level) => level,
+            Err(_) => return Err(CompilerError::ArgumentError(format!(
+                "invalid optimization level '{}'", level)))
+        },
+        None => 0
+    };
+    *OPT_LEVEL.lock().unwrap() = level;
+
     // Output all modules to 'main.rs'
     let mut filename = "main.rs".to_string();
     filename.insert_str(0, &*SRC_ROOT.lock().unwrap());
@@ -122,10 +144,248 @@ fn queue_module(module: &str) {
     }
 }
 
+/// Determines if asserts are output and `__debug__` is true, any optimization
+/// level strips them like `python -O`
+fn debug_mode() -> bool {
+    *OPT_LEVEL.lock().unwrap() == 0
+}
+
+fn push_block(block: Block) {
+    BLOCK_STACK.lock().unwrap().push(block);
+}
+
+fn pop_block() {
+    BLOCK_STACK.lock().unwrap().pop();
+}
+
+/// Runs `output` with `block` pushed, the block is popped even when `output`
+/// fails so an early return doesn't leave a stale entry behind
+fn with_block<T, F>(block: Block, output: F) -> Result<T, CompilerError>
+    where F: FnOnce() -> Result<T, CompilerError> {
+    push_block(block);
+    let result = output();
+    pop_block();
+    result
+}
+
+/// Returns the Rust statement that performs a Python `break` or `continue`
+/// (`keyword`) on the innermost loop, `None` if there's no enclosing loop. A
+/// closure between the statement and the loop is left with the `flow` variant.
+fn loop_exit_stmt(keyword: &str, flow: &str) -> Option<String> {
+    let mut in_closure = false;
+
+    for block in BLOCK_STACK.lock().unwrap().iter().rev() {
+        match *block {
+            Block::Loop(ref label) => {
+                if in_closure {
+                    return Some(format!("return cannolib::ControlFlow::{};",
+                        flow))
+                } else {
+                    return Some(format!("{} '{};", keyword, label))
+                }
+            },
+            Block::Closure => in_closure = true,
+            Block::Handler(_) => (),
+            Block::Function(_) | Block::Class => return None
+        }
+    }
+    None
+}
+
+/// Returns the Rust statement that returns `value` from the current function,
+/// `None` if the statement isn't in a function.
+fn return_stmt(value: &str) -> Option<String> {
+    let mut in_closure = false;
+
+    for block in BLOCK_STACK.lock().unwrap().iter().rev() {
+        match *block {
+            Block::Function(_) => {
+                if in_closure {
+                    return Some(format!("return cannolib::ControlFlow::\
+                        Return({});", value))
+                } else {
+                    return Some(format!("return {};", value))
+                }
+            },
+            Block::Closure => in_closure = true,
+            Block::Loop(_) | Block::Handler(_) => (),
+            Block::Class => return None
+        }
+    }
+    None
+}
+
+/// Returns the name of the first parameter of the innermost function when it's
+/// defined in a class body, this is the implicit argument to `super()`.
+fn super_self_name() -> Option<String> {
+    let stack = BLOCK_STACK.lock().unwrap();
+    let mut iter = stack.iter().rev();
+
+    let self_name = loop {
+        match iter.next() {
+            Some(&Block::Function(ref self_name)) => break self_name.clone(),
+            Some(&Block::Class) | None => return None,
+            Some(_) => ()
+        }
+    };
+
+    for block in iter {
+        if let Block::Class = *block {
+            return self_name
+        }
+    }
+    None
+}
+
+fn in_function() -> bool {
+    for block in BLOCK_STACK.lock().unwrap().iter().rev() {
+        match *block {
+            Block::Function(_) => return true,
+            Block::Class => return false,
+            _ => ()
+        }
+    }
+    false
+}
+
+/// Pushes the scope of a function, class or comprehension body, `nonlocal`
+/// names have to be bound by one of the enclosing functions
+fn push_scope(scope: Scope) -> Result<(), CompilerError> {
+    let mut stack = SCOPE_STACK.lock().unwrap();
+
+    for name in scope.nonlocals() {
+        if nonlocal_index(&stack, name).is_none() {
+            return Err(CompilerError::SyntaxError(format!("no binding for \
+                nonlocal '{}' found", name)))
+        }
+    }
+    stack.push(scope);
+    Ok(())
+}
+
+fn pop_scope() {
+    SCOPE_STACK.lock().unwrap().pop();
+}
+
+/// Like `with_block`, pops `scope` whether or not `output` succeeds
+fn with_scope<T, F>(scope: Scope, output: F) -> Result<T, CompilerError>
+    where F: FnOnce() -> Result<T, CompilerError> {
+    push_scope(scope)?;
+    let result = output();
+    pop_scope();
+    result
+}
+
+/// Finds the index into `cannoli_scope_list` of the closest enclosing function
+/// that binds `name`, skipping the innermost scope. The first two tables are
+/// the builtins and the module.
+fn nonlocal_index(stack: &[Scope], name: &str) -> Option<usize> {
+    for (ndx, scope) in stack.iter().enumerate().rev() {
+        if !scope.is_function() {
+            continue
+        }
+        match scope.binding(name) {
+            Binding::Local if scope.binds(name) => return Some(ndx + 2),
+            Binding::Local | Binding::Nonlocal => (),
+            Binding::Global => return None
+        }
+    }
+    None
+}
+
+/// Returns the table that an assignment to `name` stores into, `global` and
+/// `nonlocal` declarations take it out of the current scope
+fn output_name_scope(class_scope: bool, name: &str) -> String {
+    let class_scope = class_scope || in_class_body();
+    let stack = SCOPE_STACK.lock().unwrap();
+    let binding = match stack.last() {
+        Some(scope) => scope.binding(name),
+        None => Binding::Local
+    };
+
+    match binding {
+        Binding::Local if class_scope => "cannoli_object_tbl".to_string(),
+        Binding::Local =>
+            "cannoli_scope_list.last_mut().unwrap().borrow_mut()".to_string(),
+        Binding::Global => "cannoli_scope_list[1].borrow_mut()".to_string(),
+        Binding::Nonlocal => {
+            let ndx = nonlocal_index(&stack[..stack.len() - 1], name).unwrap();
+            format!("cannoli_scope_list[{}].borrow_mut()", ndx)
+        }
+    }
+}
+
+/// Returns the scope list that a read of `name` searches, a global name skips
+/// the tables of any enclosing functions
+fn output_name_lookup(name: &str) -> String {
+    match SCOPE_STACK.lock().unwrap().last() {
+        Some(scope) if scope.binding(name) == Binding::Global =>
+            "&cannoli_scope_list[..2]".to_string(),
+        _ => "&cannoli_scope_list".to_string()
+    }
+}
+
+/// Determines if the innermost scope is a class body. Statements nested in a
+/// class body's loops, `with` and `try` blocks are output without
+/// `class_scope` but still bind and read the class's table.
+fn in_class_body() -> bool {
+    match SCOPE_STACK.lock().unwrap().last() {
+        Some(scope) => scope.is_class(),
+        None => false
+    }
+}
+
+/// Determines if `name` is bound by the innermost class body, reads of these
+/// names check the class's table first
+fn is_class_local(name: &str) -> bool {
+    match SCOPE_STACK.lock().unwrap().last() {
+        Some(scope) => scope.is_class() && scope.binds(name),
+        None => false
+    }
+}
+
+/// Determines if `name` is local to the innermost function, reads of these
+/// names never fall through to an enclosing scope
+fn is_function_local(name: &str) -> bool {
+    match SCOPE_STACK.lock().unwrap().last() {
+        Some(scope) => scope.is_function() && scope.binds(name),
+        None => false
+    }
+}
+
+/// Returns the Rust expression that raises the error for reading or deleting
+/// `name` while it's unbound
+fn output_unbound_name_error(name: &str) -> String {
+    let (exc, msg) = if is_function_local(name) {
+        ("UnboundLocalError", format!("local variable '{}' referenced before \
+            assignment", name))
+    } else {
+        ("NameError", format!("name '{}' is not defined", name))
+    };
+
+    format!("cannolib::raise(cannolib::lookup_value(&cannoli_scope_list, \
+        \"{}\").call(vec![cannolib::Value::Str(\"{}\".to_string())], \
+        std::collections::HashMap::new()), None)", exc, util::escape(&msg))
+}
+
+/// Returns the local holding the exception of the innermost except handler
+fn innermost_handler() -> Option<Local> {
+    for block in BLOCK_STACK.lock().unwrap().iter().rev() {
+        match *block {
+            Block::Handler(ref exc) => return Some(exc.clone()),
+            Block::Function(_) | Block::Class => return None,
+            _ => ()
+        }
+    }
+    None
+}
+
 fn output_main_headers(outfile: &mut File) -> Result<(), CompilerError> {
+    // Exceptions that escape the main module are reported by cannolib
     outfile.write_all(format!("extern crate cannolib;\n\n\
-        fn main() {{\n{}main::execute()\n}}\n\n", INDENT)
-        .as_bytes()).unwrap();
+        fn main() {{\n{}if let Err(exc) = std::panic::catch_unwind(\
+        main::execute) {{\n{}cannolib::exit_with_exception(exc)\n{}}}\n}}\n\n",
+        INDENT, INDENT.repeat(2), INDENT).as_bytes()).unwrap();
 
     Ok(())
 }
@@ -209,7 +469,7 @@ fn output_module(outfile: &mut File, module: &str, ast: &Ast)
     outfile.write(INDENT.repeat(2).as_bytes()).unwrap();
     outfile.write_all(format!("cannoli_scope_list.last_mut().unwrap()\
         .borrow_mut().insert(\"__name__\".to_string(), cannolib::Value::Str(\
-        \"{}\".to_string()));\n", module).as_bytes()).unwrap();
+        \"{}\".to_string()));\n", util::escape(module)).as_bytes()).unwrap();
     outfile.write(INDENT.repeat(2).as_bytes()).unwrap();
     outfile.write_all("cannoli_scope_list.last_mut().unwrap().borrow_mut()\
         .insert(\"__module__\".to_string(), cannolib::Value::Bool(true));\n"
@@ -243,9 +503,10 @@ fn output_stmt(outfile: &mut File, class_scope: bool, indent: usize,
         Statement::FunctionDef { .. } => output_stmt_funcdef(outfile,
             class_scope, indent, stmt),
         Statement::ClassDef { .. } => output_stmt_classdef(outfile,
-            indent, stmt),
+            class_scope, indent, stmt),
         Statement::Return { .. } => output_stmt_return(outfile, indent, stmt),
-        Statement::Delete { .. } => unimplemented!(),
+        Statement::Delete { .. } => output_stmt_delete(outfile,
+            class_scope, indent, stmt),
         Statement::Assign { .. } => output_stmt_assign(outfile,
             class_scope, indent, stmt),
         Statement::AugAssign { .. } => output_stmt_aug_assign(outfile,
@@ -255,105 +516,214 @@ fn output_stmt(outfile: &mut File, class_scope: bool, indent: usize,
         Statement::For { .. } => output_stmt_for(outfile, indent, stmt),
         Statement::While { .. } => output_stmt_while(outfile, indent, stmt),
         Statement::If { .. }    => output_stmt_if(outfile, indent, stmt),
-        Statement::With { .. } => unimplemented!(),
-        Statement::Raise { .. } => unimplemented!(),
-        Statement::Try { .. } => unimplemented!(),
-        Statement::Assert { .. } => unimplemented!(),
+        Statement::With { .. } => output_stmt_with(outfile, indent, stmt),
+        Statement::Raise { .. } => output_stmt_raise(outfile, indent, stmt),
+        Statement::Try { .. } => output_stmt_try(outfile, indent, stmt),
+        Statement::Assert { .. } => output_stmt_assert(outfile, indent, stmt),
         Statement::Import { .. } => output_stmt_import(outfile, indent, stmt),
         Statement::ImportFrom { .. } => output_stmt_import_from(outfile,
             indent, stmt),
-        Statement::Global { .. } => unimplemented!(),
-        Statement::Nonlocal { .. } => unimplemented!(),
+        Statement::Global { .. } => Ok(()),
+        Statement::Nonlocal { .. } => output_stmt_nonlocal(),
         Statement::Expr { .. }  => output_stmt_expr(outfile, indent, stmt),
         Statement::Pass => Ok(()),
-        Statement::Break => unimplemented!(),
-        Statement::Continue => unimplemented!()
+        Statement::Break => output_stmt_break(outfile, indent),
+        Statement::Continue => output_stmt_continue(outfile, indent)
     }
 }
 
 fn output_stmt_funcdef(outfile: &mut File, class_scope: bool, indent: usize,
     stmt: &Statement) -> Result<(), CompilerError> {
-    let (name, args, body, _decorator_list, _returns) = match *stmt {
+    let (name, args, body, decorator_list, _returns) = match *stmt {
         Statement::FunctionDef { ref name, ref args, ref body,
             ref decorator_list, ref returns } =>
             (name, args, body, decorator_list, returns),
         _ => unreachable!()
     };
-    let mut prefix = String::new();
     let local = Local::new();
 
-    if class_scope {
-        prefix.push_str("cannoli_object_tbl");
-    } else {
-        prefix.push_str("cannoli_scope_list.last_mut().unwrap().borrow_mut()");
-    }
+    let decorators = output_decorators(outfile, indent, decorator_list)?;
+    let signature = output_parameters(outfile, indent, name, args)?;
 
-    // Setup function signature and append to the scope list
-    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write("let move_scope = cannoli_scope_list.clone();\n"
-        .as_bytes()).unwrap();
-    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write(format!("let mut {} = cannolib::Value::Function(std::rc::Rc\
-        ::new(move |cannoli_func_args: Vec<cannolib::Value>, mut kwargs: \
-        std::collections::HashMap<String, cannolib::Value>| -> cannolib::Value \
-        {{\n", local).as_bytes()).unwrap();
-    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-    outfile.write("let mut cannoli_scope_list = move_scope.clone();\n"
-        .as_bytes()).unwrap();
-    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-    outfile.write("cannoli_scope_list.push(std::rc::Rc::new(std::cell::RefCell\
-        ::new(std::collections::HashMap::new())));\n".as_bytes()).unwrap();
+    output_func_header(outfile, indent, &local, &signature)?;
 
-    // setup parameters
-    output_parameters(outfile, indent + 1, args)?;
-    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-    outfile.write("cannoli_scope_list.last_mut().unwrap().borrow_mut()\
-        .extend(kwargs);\n".as_bytes()).unwrap();
-    output_stmts(outfile, false, indent + 1, body)?;
+    let is_generator = util::stmts_contain_yield(body);
+    let body_indent = if is_generator { indent + 2 } else { indent + 1 };
+    if is_generator {
+        output_generator_header(outfile, indent + 1)?;
+    }
+
+    with_scope(Scope::function(args, body)?, || {
+        with_block(Block::Function(first_arg_name(args)), || {
+            output_stmts(outfile, false, body_indent, body)
+        })
+    })?;
 
     // output default return value (None) and closing bracket
-    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(body_indent).as_bytes()).unwrap();
     outfile.write("cannolib::Value::None\n".as_bytes()).unwrap();
+    if is_generator {
+        outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+        outfile.write("}))\n".as_bytes()).unwrap();
+    }
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write("}));\n".as_bytes()).unwrap();
+
+    let local = output_apply_decorators(outfile, indent, &decorators, local)?;
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write(format!("{}.insert(\"{}\".to_string(), {});\n",
-        prefix, name, local).as_bytes()).unwrap();
+        output_name_scope(class_scope, name), util::escape(name), local)
+        .as_bytes()).unwrap();
     outfile.flush().unwrap();
 
     Ok(())
 }
 
-fn output_stmt_classdef(outfile: &mut File, indent: usize, stmt: &Statement)
-    -> Result<(), CompilerError> {
-    let (name, _, _, body, _) = match *stmt {
+/// Outputs the class body in its own Rust block, the resulting table is
+/// passed with the bases to cannolib which computes the MRO. A scope holding
+/// `__class__` is pushed for the methods defined in the body, it's bound once
+/// the class is built so that `super()` can find it.
+fn output_stmt_classdef(outfile: &mut File, class_scope: bool, indent: usize,
+    stmt: &Statement) -> Result<(), CompilerError> {
+    let (name, bases, keywords, body, decorator_list) = match *stmt {
         Statement::ClassDef { ref name, ref bases, ref keywords, ref body,
             ref decorator_list } => (name, bases, keywords, body,
             decorator_list),
         _ => unreachable!()
     };
+    let local = Local::new();
+
+    let decorators = output_decorators(outfile, indent, decorator_list)?;
+
+    // Bases and keywords are evaluated in the enclosing scope
+    let mut bases_arg = String::new();
+    for base in bases.iter() {
+        let base_local = output_expr(outfile, indent, base)?;
+        bases_arg.push_str(&format!("{}, ", base_local));
+    }
+    bases_arg.pop();
+    bases_arg.pop();
+
+    let mut kwargs_output = String::new();
+    let kwargs_local = output_keywords(outfile, indent, keywords,
+        &mut kwargs_output)?;
+    outfile.write_all(kwargs_output.as_bytes()).unwrap();
 
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("cannoli_scope_list.push(std::rc::Rc::new(\
+        std::cell::RefCell::new(std::collections::HashMap::new())));\n"
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = {{\n", local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
     outfile.write("let mut cannoli_object_tbl = \
         std::collections::HashMap::new();\n".as_bytes()).unwrap();
 
-    output_stmts(outfile, true, indent, body)?;
+    with_scope(Scope::class(body)?, || {
+        with_block(Block::Class, || {
+            output_stmts(outfile, true, indent + 1, body)
+        })
+    })?;
 
     // Add meta information into the table
-    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
     outfile.write(format!("cannoli_object_tbl.insert(\"__name__\"\
         .to_string(), cannolib::Value::Str(\"{}\".to_string()));\n",
-        name).as_bytes()).unwrap();
+        util::escape(name)).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::build_class(cannoli_object_tbl, \
+        vec![{}], {})\n", bases_arg, kwargs_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("};\n".as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("cannoli_scope_list.pop().unwrap().borrow_mut()\
+        .insert(\"__class__\".to_string(), {}.clone());\n", local)
+        .as_bytes()).unwrap();
+
+    let local = output_apply_decorators(outfile, indent, &decorators, local)?;
 
     // Add the new class definition to the current scope table
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all(format!("cannoli_scope_list.last_mut().unwrap()\
-        .borrow_mut().insert(\"{}\".to_string(), cannolib::Value::Class {{ \
-        tbl: cannoli_object_tbl }});\n", name).as_bytes()).unwrap();
+    outfile.write_all(format!("{}.insert(\"{}\".to_string(), {});\n",
+        output_name_scope(class_scope, name), util::escape(name), local)
+        .as_bytes()).unwrap();
+
+    Ok(())
+}
+
+/// Outputs the start of the closure for a function or lambda, it captures the
+/// scope list and binds its arguments into a new local scope.
+fn output_func_header(outfile: &mut File, indent: usize, local: &Local,
+    signature: &Local) -> Result<(), CompilerError> {
+    // Setup function signature and append to the scope list
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write("let move_scope = cannoli_scope_list.clone();\n"
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write(format!("let mut {} = cannolib::Value::Function(std::rc::Rc\
+        ::new(move |cannoli_func_args: Vec<cannolib::Value>, kwargs: \
+        std::collections::HashMap<String, cannolib::Value>| -> cannolib::Value \
+        {{\n", local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write("let mut cannoli_scope_list = move_scope.clone();\n"
+        .as_bytes()).unwrap();
+
+    // Bind the arguments into the function's local scope
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write(format!("cannoli_scope_list.push(std::rc::Rc::new(\
+        std::cell::RefCell::new({}.bind(cannoli_func_args, kwargs))));\n",
+        signature).as_bytes()).unwrap();
+
+    Ok(())
+}
+
+/// Outputs the start of the closure run by the generator that calling a
+/// generator function returns. Cannolib resumes the closure each time a value
+/// is requested, `cannoli_yielder` suspends it at every `yield`.
+fn output_generator_header(outfile: &mut File, indent: usize)
+    -> Result<(), CompilerError> {
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write("cannolib::Value::Generator(cannolib::GeneratorType::new(\
+        move |cannoli_yielder: cannolib::Yielder| -> cannolib::Value {\n"
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write("let mut cannoli_scope_list = cannoli_scope_list;\n"
+        .as_bytes()).unwrap();
 
     Ok(())
 }
 
+/// Outputs the decorator expressions in order, returning their locals. These
+/// are evaluated before the function or class they decorate.
+fn output_decorators(outfile: &mut File, indent: usize,
+    decorator_list: &Vec<Expression>) -> Result<Vec<Local>, CompilerError> {
+    let mut decorators = vec![];
+
+    for decorator in decorator_list.iter() {
+        decorators.push(output_expr(outfile, indent, decorator)?);
+    }
+    Ok(decorators)
+}
+
+/// Calls each decorator on the decorated value from the bottom up, returns
+/// the local holding the value that gets bound to the name.
+fn output_apply_decorators(outfile: &mut File, indent: usize,
+    decorators: &Vec<Local>, value: Local) -> Result<Local, CompilerError> {
+    let mut value = value;
+
+    for decorator in decorators.iter().rev() {
+        let local = Local::new();
+
+        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+        outfile.write_all(format!("let mut {} = {}.call(vec![{}], \
+            std::collections::HashMap::new());\n", local, decorator, value)
+            .as_bytes()).unwrap();
+        value = local;
+    }
+    Ok(value)
+}
+
 fn output_stmt_return(outfile: &mut File, indent: usize, stmt: &Statement)
     -> Result<(), CompilerError> {
     let value = match *stmt {
@@ -361,21 +731,97 @@ fn output_stmt_return(outfile: &mut File, indent: usize, stmt: &Statement)
         _ => unreachable!()
     };
 
-    match *value {
-        Some(ref value) => {
-            let value_local = output_expr(outfile, indent, value)?;
+    let value_str = match *value {
+        Some(ref value) => output_expr(outfile, indent, value)?.get_label(),
+        None => "cannolib::Value::None".to_string()
+    };
+    let return_str = match return_stmt(&value_str) {
+        Some(return_str) => return_str,
+        None => return Err(CompilerError::SyntaxError("'return' outside \
+            function".to_string()))
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("{}\n", return_str).as_bytes()).unwrap();
+    Ok(())
+}
+
+fn output_stmt_delete(outfile: &mut File, class_scope: bool, indent: usize,
+    stmt: &Statement) -> Result<(), CompilerError> {
+    let targets = match *stmt {
+        Statement::Delete { ref targets } => targets,
+        _ => unreachable!()
+    };
+
+    for target in targets.iter() {
+        output_delete_target(outfile, class_scope, indent, target)?;
+    }
+    Ok(())
+}
+
+/// Removes a name's binding, raising a `NameError` if it isn't bound, or asks
+/// the object to remove an attribute or element. Tuple and list targets are
+/// deleted from left to right.
+fn output_delete_target(outfile: &mut File, class_scope: bool, indent: usize,
+    target: &Expression) -> Result<(), CompilerError> {
+    match *target {
+        Expression::Name { ref id, .. } => {
+            let removed_local = Local::new();
 
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-            outfile.write_all(format!("return {};\n", value_local)
+            outfile.write_all(format!("let mut {} = {}.remove(\"{}\");\n",
+                removed_local, output_name_scope(class_scope, id),
+                util::escape(id))
                 .as_bytes()).unwrap();
-        },
-        None => {
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-            outfile.write_all("return cannolib::Value::None;\n"
+            outfile.write_all(format!("if {}.is_none() {{\n", removed_local)
                 .as_bytes()).unwrap();
-        }
-    }
+            outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+            outfile.write_all(format!("{};\n", output_unbound_name_error(id))
+                .as_bytes()).unwrap();
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            outfile.write_all("}\n".as_bytes()).unwrap();
+        },
+        Expression::Attribute { ref value, ref attr, .. } => {
+            let base_local = output_expr(outfile, indent, value)?;
+
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            outfile.write_all(format!("cannolib::attr_delete({}, \"{}\");\n",
+                base_local, util::escape(attr)).as_bytes()).unwrap();
+        },
+        Expression::Subscript { ref value, ref slice, .. } => {
+            let base_local = output_expr(outfile, indent, value)?;
 
+            match **slice {
+                Slice::Slice { ref lower, ref upper, ref step } => {
+                    let bounds = output_slice_bounds(outfile, indent, lower,
+                        upper, step)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("{}.del_slice({});\n",
+                        base_local, bounds).as_bytes()).unwrap();
+                },
+                Slice::ExtSlice { .. } =>
+                    return Err(CompilerError::NotImplementedError(
+                        "extended slices".to_string())),
+                Slice::Index { ref value } => {
+                    let index_local = output_expr(outfile, indent, value)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("{}.del_index({});\n",
+                        base_local, index_local).as_bytes()).unwrap();
+                }
+            }
+        },
+        Expression::List { ref elts, .. } |
+        Expression::Tuple { ref elts, .. } => {
+            for elt in elts.iter() {
+                output_delete_target(outfile, class_scope, indent, elt)?;
+            }
+        },
+        _ => return Err(CompilerError::SyntaxError("can't delete \
+            expression".to_string()))
+    }
     Ok(())
 }
 
@@ -385,13 +831,6 @@ fn output_stmt_assign(outfile: &mut File, class_scope: bool, indent: usize,
         Statement::Assign { ref targets, ref value } => (targets, value),
         _ => unreachable!()
     };
-    let mut prefix = String::new();
-
-    if class_scope {
-        prefix.push_str("cannoli_object_tbl");
-    } else {
-        prefix.push_str("cannoli_scope_list.last_mut().unwrap().borrow_mut()");
-    }
 
     // For each target determine if it's a Name/Attribute/Subscript and handle
     // each differently. Name values should be inserted into the current scope
@@ -400,12 +839,14 @@ fn output_stmt_assign(outfile: &mut File, class_scope: bool, indent: usize,
     // but only work on lists and dicts.
     let value_local = output_expr(outfile, indent, value)?;
     for target in targets.iter() {
-        unpack_values(outfile, indent, Some(&prefix), &value_local, target)?;
+        unpack_values(outfile, indent, class_scope, &value_local, target)?;
     }
     Ok(())
 }
 
-// TODO ignores class_scope for now, I don't know if it even works in Python.
+/// The target's base and index are evaluated once, before the value. The
+/// current value is read from them and the result of the in-place operation is
+/// stored back.
 fn output_stmt_aug_assign(outfile: &mut File, class_scope: bool, indent: usize,
     stmt: &Statement) -> Result<(), CompilerError> {
     let (target, op, value) = match *stmt {
@@ -413,34 +854,137 @@ fn output_stmt_aug_assign(outfile: &mut File, class_scope: bool, indent: usize,
             (target, op, value),
         _ => unreachable!()
     };
-    let mut prefix = String::new();
-
-    if class_scope {
-        prefix.push_str("cannoli_object_tbl");
-    } else {
-        prefix.push_str("cannoli_scope_list.last_mut().unwrap().borrow_mut()");
-    }
+    let current_local = Local::new();
 
-    let value_local = output_expr(outfile, indent, value)?;
     match *target {
         Expression::Name { ref id, .. } => {
-            let local = Local::new();
+            // A class body reads its own table before the enclosing scopes
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            if is_class_local(id) {
+                outfile.write_all(format!("let mut {} = match \
+                    cannoli_object_tbl.get(\"{}\") {{ Some(val) => \
+                    val.clone(), None => cannolib::lookup_value({}, \"{}\") \
+                    }};\n",
+                    current_local, util::escape(id), output_name_lookup(id),
+                    util::escape(id))
+                    .as_bytes()).unwrap();
+            } else {
+                outfile.write_all(format!("let mut {} = cannolib::lookup_value(\
+                    {}, \"{}\");\n", current_local, output_name_lookup(id),
+                    util::escape(id))
+                    .as_bytes()).unwrap();
+            }
+
+            let value_local = output_expr(outfile, indent, value)?;
+            let result_local = output_inplace_op(outfile, indent,
+                &current_local, op, &value_local)?;
 
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-            outfile.write_all(format!("let mut {} = cannolib::lookup_value(\
-                &cannoli_scope_list, \"{}\");\n", local, id)
+            outfile.write_all(format!("{}.insert(\"{}\".to_string(), {});\n",
+                output_name_scope(class_scope, id), util::escape(id),
+                result_local)
                 .as_bytes()).unwrap();
+        },
+        Expression::Attribute { value: ref base, ref attr, .. } => {
+            let base_local = output_expr(outfile, indent, base)?;
+
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            outfile.write_all(format!("let mut {} = {}.get_attr(\"{}\");\n",
+                current_local, base_local, util::escape(attr)).as_bytes())
+                .unwrap();
+
+            let value_local = output_expr(outfile, indent, value)?;
+            let result_local = output_inplace_op(outfile, indent,
+                &current_local, op, &value_local)?;
+
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-            outfile.write_all(format!("cannoli_scope_list.last_mut().unwrap()\
-                .borrow_mut().insert(\"{}\".to_string(), {});\n", id,
-                output_operator(&local, op, &value_local)?)
+            outfile.write_all(format!("cannolib::attr_assign({}, \"{}\", {}\
+                );\n", base_local, util::escape(attr), result_local)
                 .as_bytes()).unwrap();
         },
-        _ => panic!("illegal expression for augmented assignment")
+        Expression::Subscript { value: ref base, ref slice, .. } => {
+            let base_local = output_expr(outfile, indent, base)?;
+
+            match **slice {
+                Slice::Slice { ref lower, ref upper, ref step } => {
+                    let bounds_local = Local::new();
+                    let bounds = output_slice_bounds(outfile, indent, lower,
+                        upper, step)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("let mut {} = ({});\n",
+                        bounds_local, bounds).as_bytes()).unwrap();
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("let mut {} = {}.slice(\
+                        {}.0.clone(), {}.1.clone(), {}.2.clone());\n",
+                        current_local, base_local, bounds_local, bounds_local,
+                        bounds_local).as_bytes()).unwrap();
+
+                    let value_local = output_expr(outfile, indent, value)?;
+                    let result_local = output_inplace_op(outfile, indent,
+                        &current_local, op, &value_local)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("{}.set_slice({}.0, {}.1, {}.2, \
+                        {});\n", base_local, bounds_local, bounds_local,
+                        bounds_local, result_local).as_bytes()).unwrap();
+                },
+                Slice::ExtSlice { .. } =>
+                    return Err(CompilerError::NotImplementedError(
+                        "extended slices".to_string())),
+                Slice::Index { value: ref index } => {
+                    let index_local = output_expr(outfile, indent, index)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("let mut {} = {}.index(\
+                        {}.clone());\n", current_local, base_local,
+                        index_local).as_bytes()).unwrap();
+
+                    let value_local = output_expr(outfile, indent, value)?;
+                    let result_local = output_inplace_op(outfile, indent,
+                        &current_local, op, &value_local)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("{}.set_index({}, {});\n",
+                        base_local, index_local, result_local).as_bytes())
+                        .unwrap();
+                }
+            }
+        },
+        _ => return Err(CompilerError::SyntaxError("illegal expression for \
+            augmented assignment".to_string()))
     }
     Ok(())
 }
 
+/// Outputs an augmented operation, the in-place method (`__iadd__`, etc.) is
+/// used when the value defines it and the binary operator otherwise.
+fn output_inplace_op(outfile: &mut File, indent: usize, lft: &Local,
+    op: &Operator, rht: &Local) -> Result<Local, CompilerError> {
+    let local = Local::new();
+    let method = match *op {
+        Operator::Add => "__iadd__",
+        Operator::Sub => "__isub__",
+        Operator::Mult => "__imul__",
+        Operator::MatMult => "__imatmul__",
+        Operator::Div => "__itruediv__",
+        Operator::Mod => "__imod__",
+        Operator::Pow => "__ipow__",
+        Operator::LShift => "__ilshift__",
+        Operator::RShift => "__irshift__",
+        Operator::BitOr => "__ior__",
+        Operator::BitXor => "__ixor__",
+        Operator::BitAnd => "__iand__",
+        Operator::FloorDiv => "__ifloordiv__"
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = match {}.inplace_op(\"{}\", \
+        &{}) {{ Some(val) => val, None => {} }};\n", local, lft, method, rht,
+        output_operator(lft, op, rht)?).as_bytes()).unwrap();
+    Ok(local)
+}
+
 fn output_stmt_ann_assign(outfile: &mut File, class_scope: bool, indent: usize,
     stmt: &Statement) -> Result<(), CompilerError> {
     let (target, _annotation, value) = match *stmt {
@@ -453,51 +997,69 @@ fn output_stmt_ann_assign(outfile: &mut File, class_scope: bool, indent: usize,
         },
         _ => unreachable!()
     };
-    let mut prefix = String::new();
-
-    if class_scope {
-        prefix.push_str("cannoli_object_tbl");
-    } else {
-        prefix.push_str("cannoli_scope_list.last_mut().unwrap().borrow_mut()");
-    }
 
     let value_local = output_expr(outfile, indent, value)?;
-    unpack_values(outfile, indent, Some(&prefix), &value_local, target)?;
+    unpack_values(outfile, indent, class_scope, &value_local, target)?;
 
     Ok(())
 }
 
-// TODO add support for for-else
+/// Outputs a labeled Rust `loop` over a clone of the sequence. The `else` body
+/// is output in the branch taken when the iterator is exhausted, a `break`
+/// skips it by leaving the loop from elsewhere.
 fn output_stmt_for(outfile: &mut File, indent: usize, stmt: &Statement)
     -> Result<(), CompilerError> {
-    let (target, iter, body, _orelse) = match *stmt {
+    let (target, iter, body, orelse) = match *stmt {
         Statement::For { ref target, ref iter, ref body, ref orelse } =>
             (target, iter, body, orelse),
         _ => unreachable!()
     };
     let iter_local = Local::new();
+    let loop_label = Local::new();
     let seq_local = output_expr(outfile, indent, iter)?;
 
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all(format!("let mut {} = {}.clone_seq().into_iter();\n",
+    outfile.write_all(format!("let mut {} = cannolib::iter({});\n",
         iter_local, seq_local).as_bytes()).unwrap();
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all("loop {\n".as_bytes()).unwrap();
+    outfile.write_all(format!("'{}: loop {{\n", loop_label)
+        .as_bytes()).unwrap();
 
     let next_local = Local::new();
     outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-    outfile.write_all(format!("let mut {} = if let Some(val) = \
-        {}.next() {{ val }} else {{ break }};\n", next_local,
-        iter_local).as_bytes()).unwrap();
+    if orelse.is_empty() {
+        outfile.write_all(format!("let mut {} = if let Some(val) = \
+            {}.next() {{ val }} else {{ break '{} }};\n", next_local,
+            iter_local, loop_label).as_bytes()).unwrap();
+    } else {
+        outfile.write_all(format!("let mut {} = if let Some(val) = \
+            {}.next() {{ val }} else {{\n", next_local, iter_local)
+            .as_bytes()).unwrap();
 
-    unpack_values(outfile, indent + 1, None, &next_local, target)?;
-    output_stmts(outfile, false, indent + 1, body)?;
+        // `break` and `continue` in the else body refer to an enclosing loop
+        output_stmts(outfile, false, indent + 2, orelse)?;
+
+        outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+        outfile.write_all(format!("break '{};\n", loop_label)
+            .as_bytes()).unwrap();
+        outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+        outfile.write_all("};\n".as_bytes()).unwrap();
+    }
+
+    unpack_values(outfile, indent + 1, false, &next_local, target)?;
+
+    with_block(Block::Loop(loop_label), || {
+        output_stmts(outfile, false, indent + 1, body)
+    })?;
 
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write_all("}\n".as_bytes()).unwrap();
     Ok(())
 }
 
+/// Outputs a labeled Rust `loop` that evaluates the condition once at the top
+/// of each iteration. The `else` body is output in the branch taken when the
+/// condition fails, a `break` skips it by leaving the loop from elsewhere.
 fn output_stmt_while(outfile: &mut File, indent: usize, stmt: &Statement)
     -> Result<(), CompilerError> {
     let (test, body, orelse) = match *stmt {
@@ -505,35 +1067,32 @@ fn output_stmt_while(outfile: &mut File, indent: usize, stmt: &Statement)
             (test, body, orelse),
         _ => unreachable!()
     };
+    let loop_label = Local::new();
 
-    let condition = output_expr(outfile, indent, test)?;
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all(format!("while ({}).to_bool() {{\n",
+    outfile.write_all(format!("'{}: loop {{\n", loop_label)
+        .as_bytes()).unwrap();
+
+    let condition = output_expr(outfile, indent + 1, test)?;
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("if !({}).to_bool() {{\n",
         condition).as_bytes()).unwrap();
 
-    output_stmts(outfile, false, indent + 1, body)?;
+    // `break` and `continue` in the else body refer to an enclosing loop
+    output_stmts(outfile, false, indent + 2, orelse)?;
 
-    // update the condition variable
-    let loop_cond = output_expr(outfile, indent + 1, test)?;
-    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-    outfile.write_all(format!("{} = {};\n", condition, loop_cond)
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("break '{};\n", loop_label)
         .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+
+    with_block(Block::Loop(loop_label), || {
+        output_stmts(outfile, false, indent + 1, body)
+    })?;
 
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write_all("}\n".as_bytes()).unwrap();
-
-    if !orelse.is_empty() {
-        // Negate the WHILE condition and add an if-statement
-        let condition = output_expr(outfile, indent, test)?;
-        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-        outfile.write_all(format!("if !({}).to_bool() {{\n",
-            condition).as_bytes()).unwrap();
-
-        output_stmts(outfile, false, indent + 1, orelse)?;
-
-        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-        outfile.write_all("}\n".as_bytes()).unwrap();
-    }
     Ok(())
 }
 
@@ -571,6 +1130,472 @@ fn output_stmt_if(outfile: &mut File, indent: usize, stmt: &Statement)
     Ok(())
 }
 
+fn output_stmt_raise(outfile: &mut File, indent: usize, stmt: &Statement)
+    -> Result<(), CompilerError> {
+    let (exc, cause) = match *stmt {
+        Statement::Raise { ref exc, ref cause } => (exc, cause),
+        _ => unreachable!()
+    };
+
+    let exc = match *exc {
+        Some(ref exc) => exc,
+        None => {
+            // A bare raise re-raises the exception being handled
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            match innermost_handler() {
+                Some(exc_local) => {
+                    outfile.write_all(format!("cannolib::reraise(\
+                        {}.clone());\n", exc_local).as_bytes()).unwrap();
+                },
+                None => {
+                    outfile.write_all("cannolib::raise(cannolib::lookup_value(\
+                        &cannoli_scope_list, \"RuntimeError\").call(vec![\
+                        cannolib::Value::Str(\"No active exception to reraise\"\
+                        .to_string())], std::collections::HashMap::new()), \
+                        None);\n".as_bytes()).unwrap();
+                }
+            }
+            return Ok(())
+        }
+    };
+    let exc_local = output_expr(outfile, indent, exc)?;
+    let cause_arg = match *cause {
+        Some(ref cause) => {
+            let cause_local = output_expr(outfile, indent, cause)?;
+            format!("Some({})", cause_local)
+        },
+        None => "None".to_string()
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::raise({}, {});\n", exc_local,
+        cause_arg).as_bytes()).unwrap();
+    Ok(())
+}
+
+/// The message is only evaluated when the assertion fails
+fn output_stmt_assert(outfile: &mut File, indent: usize, stmt: &Statement)
+    -> Result<(), CompilerError> {
+    let (test, msg) = match *stmt {
+        Statement::Assert { ref test, ref msg } => (test, msg),
+        _ => unreachable!()
+    };
+
+    if !debug_mode() {
+        return Ok(())
+    }
+
+    let test_local = output_expr(outfile, indent, test)?;
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("if !({}).to_bool() {{\n", test_local)
+        .as_bytes()).unwrap();
+
+    let exc_local = Local::new();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannolib::lookup_value(\
+        &cannoli_scope_list, \"AssertionError\");\n", exc_local)
+        .as_bytes()).unwrap();
+
+    let exc_local = match *msg {
+        Some(ref msg) => {
+            let msg_local = output_expr(outfile, indent + 1, msg)?;
+            let local = Local::new();
+
+            outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+            outfile.write_all(format!("let mut {} = {}.call(vec![{}], \
+                std::collections::HashMap::new());\n", local, exc_local,
+                msg_local).as_bytes()).unwrap();
+            local
+        },
+        None => exc_local
+    };
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::raise({}, None);\n", exc_local)
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    Ok(())
+}
+
+/// Exceptions are raised by unwinding, `try` bodies are run inside a closure
+/// passed to `std::panic::catch_unwind`. A `finally` body is protected the
+/// same way so it runs whether the statement completes, leaves early or
+/// raises. Control flow that leaves a closure is returned as a
+/// `cannolib::ControlFlow` and dispatched once the closure completes.
+fn output_stmt_try(outfile: &mut File, indent: usize, stmt: &Statement)
+    -> Result<(), CompilerError> {
+    let (body, handlers, orelse, finalbody) = match *stmt {
+        Statement::Try { ref body, ref handlers, ref orelse, ref finalbody } =>
+            (body, handlers, orelse, finalbody),
+        _ => unreachable!()
+    };
+    let scope_len = Local::new();
+
+    // Scopes pushed by the body are left on the list when it unwinds
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannoli_scope_list.len();\n",
+        scope_len).as_bytes()).unwrap();
+
+    if finalbody.is_empty() {
+        let flow_local = output_try_except(outfile, indent, &scope_len, body,
+            handlers, orelse)?;
+        return output_flow_dispatch(outfile, indent, &flow_local)
+    }
+
+    let result_local = Local::new();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = std::panic::catch_unwind(\
+        std::panic::AssertUnwindSafe(|| -> cannolib::ControlFlow {{\n",
+        result_local).as_bytes()).unwrap();
+
+    with_block(Block::Closure, || {
+        if handlers.is_empty() {
+            output_stmts(outfile, false, indent + 1, body)?;
+            outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+            outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes())
+                .unwrap();
+        } else {
+            let flow_local = output_try_except(outfile, indent + 1,
+                &scope_len, body, handlers, orelse)?;
+            outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+            outfile.write_all(format!("{}\n", flow_local).as_bytes())
+                .unwrap();
+        }
+        Ok(())
+    })?;
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}));\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("cannoli_scope_list.truncate({});\n", scope_len)
+        .as_bytes()).unwrap();
+
+    // Control flow in the finally body takes precedence over the result
+    output_stmts(outfile, false, indent, finalbody)?;
+
+    let flow_local = Local::new();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = match {} {{\n", flow_local,
+        result_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Ok(flow) => flow,\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Err(payload) => std::panic::resume_unwind(payload)\n"
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("};\n".as_bytes()).unwrap();
+
+    output_flow_dispatch(outfile, indent, &flow_local)
+}
+
+/// Outputs the body of a try statement along with its handlers and `else`
+/// body, returns the local holding the resulting `cannolib::ControlFlow`.
+fn output_try_except(outfile: &mut File, indent: usize, scope_len: &Local,
+    body: &Vec<Statement>, handlers: &Vec<ExceptHandler>,
+    orelse: &Vec<Statement>) -> Result<Local, CompilerError> {
+    let result_local = Local::new();
+    let flow_local = Local::new();
+    let exc_local = Local::new();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = std::panic::catch_unwind(\
+        std::panic::AssertUnwindSafe(|| -> cannolib::ControlFlow {{\n",
+        result_local).as_bytes()).unwrap();
+
+    with_block(Block::Closure, || {
+        output_stmts(outfile, false, indent + 1, body)
+    })?;
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}));\n".as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = match {} {{\n", flow_local,
+        result_local).as_bytes()).unwrap();
+
+    // The else body only runs when the try body completes normally and the
+    // handlers don't apply to exceptions raised in it
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Ok(cannolib::ControlFlow::Next) => {\n"
+        .as_bytes()).unwrap();
+    output_stmts(outfile, false, indent + 2, orelse)?;
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("},\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Ok(flow) => flow,\n".as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Err(payload) => {\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("cannoli_scope_list.truncate({});\n", scope_len)
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannolib::exception_from_panic(\
+        payload);\n", exc_local).as_bytes()).unwrap();
+
+    rec_output_handlers(outfile, indent + 2, scope_len, &exc_local,
+        handlers.iter())?;
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("};\n".as_bytes()).unwrap();
+
+    Ok(flow_local)
+}
+
+/// Recursively outputs the except handlers as nested if-statements, each
+/// handler's class is only evaluated when the previous handlers didn't match.
+/// An exception that no handler matches is re-raised.
+fn rec_output_handlers(outfile: &mut File, indent: usize, scope_len: &Local,
+    exc_local: &Local, mut iter: Iter<ExceptHandler>)
+    -> Result<(), CompilerError> {
+    let (etype, name, body) = match iter.next() {
+        Some(&ExceptHandler::ExceptHandler { ref etype, ref name,
+            ref body }) => (etype, name, body),
+        None => {
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            outfile.write_all(format!("cannolib::reraise({})\n", exc_local)
+                .as_bytes()).unwrap();
+            return Ok(())
+        }
+    };
+    let body_indent = match *etype {
+        Some(ref etype) => {
+            let etype_local = output_expr(outfile, indent, etype)?;
+
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            outfile.write_all(format!("if cannolib::exception_matches(&{}, \
+                &{}) {{\n", exc_local, etype_local).as_bytes()).unwrap();
+            indent + 1
+        },
+        None => indent
+    };
+
+    if let Some(ref name) = *name {
+        let target = Expression::Name { id: name.clone(),
+            ctx: ExprContext::Store };
+        let exc_clone = Local::new();
+
+        outfile.write(INDENT.repeat(body_indent).as_bytes()).unwrap();
+        outfile.write_all(format!("let mut {} = {}.clone();\n", exc_clone,
+            exc_local).as_bytes()).unwrap();
+        unpack_values(outfile, body_indent, false, &exc_clone, &target)?;
+    }
+
+    with_block(Block::Handler(exc_local.clone()), || {
+        match *name {
+            Some(ref name) => output_named_handler_body(outfile, body_indent,
+                scope_len, name, body),
+            None => {
+                output_stmts(outfile, false, body_indent, body)?;
+                outfile.write(INDENT.repeat(body_indent).as_bytes()).unwrap();
+                outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes())
+                    .unwrap();
+                Ok(())
+            }
+        }
+    })?;
+
+    if etype.is_some() {
+        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+        outfile.write_all("} else {\n".as_bytes()).unwrap();
+        rec_output_handlers(outfile, indent + 1, scope_len, exc_local, iter)?;
+        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+        outfile.write_all("}\n".as_bytes()).unwrap();
+    }
+    Ok(())
+}
+
+/// The target name of a handler is cleared however the handler is left, so
+/// its body runs in a closure passed to `catch_unwind` like a try body with a
+/// `finally`. Evaluates to the `cannolib::ControlFlow` of the body.
+fn output_named_handler_body(outfile: &mut File, indent: usize,
+    scope_len: &Local, name: &str, body: &Vec<Statement>)
+    -> Result<(), CompilerError> {
+    let result_local = Local::new();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = std::panic::catch_unwind(\
+        std::panic::AssertUnwindSafe(|| -> cannolib::ControlFlow {{\n",
+        result_local).as_bytes()).unwrap();
+
+    with_block(Block::Closure, || {
+        output_stmts(outfile, false, indent + 1, body)
+    })?;
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}));\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("cannoli_scope_list.truncate({});\n", scope_len)
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("{}.remove(\"{}\");\n",
+        output_name_scope(false, name), util::escape(name))
+        .as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("match {} {{\n", result_local).as_bytes())
+        .unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Ok(flow) => flow,\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Err(payload) => std::panic::resume_unwind(payload)\n"
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    Ok(())
+}
+
+/// A statement with multiple items is output as nested with statements, one
+/// per item.
+fn output_stmt_with(outfile: &mut File, indent: usize, stmt: &Statement)
+    -> Result<(), CompilerError> {
+    let (items, body) = match *stmt {
+        Statement::With { ref items, ref body } => (items, body),
+        _ => unreachable!()
+    };
+
+    rec_output_with_items(outfile, indent, items.iter(), body)
+}
+
+/// Outputs the context manager of one with item, the rest of the items and the
+/// body run in a closure passed to `catch_unwind` so that `__exit__` is called
+/// however it's left. `__exit__` is passed the exception that's unwinding, if
+/// any, and suppresses it by returning a true value.
+fn rec_output_with_items(outfile: &mut File, indent: usize,
+    mut iter: Iter<WithItem>, body: &Vec<Statement>)
+    -> Result<(), CompilerError> {
+    let (context_expr, optional_vars) = match iter.next() {
+        Some(&WithItem::WithItem { ref context_expr, ref optional_vars }) =>
+            (context_expr, optional_vars),
+        None => return output_stmts(outfile, false, indent, body)
+    };
+    let exit_local = Local::new();
+    let enter_local = Local::new();
+    let scope_len = Local::new();
+    let result_local = Local::new();
+    let flow_local = Local::new();
+    let exc_local = Local::new();
+
+    // `__exit__` is looked up before `__enter__` is called
+    let manager_local = output_expr(outfile, indent, context_expr)?;
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = {}.get_attr(\"__exit__\");\n",
+        exit_local, manager_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = {}.get_attr(\"__enter__\")\
+        .call(vec![], std::collections::HashMap::new());\n", enter_local,
+        manager_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannoli_scope_list.len();\n",
+        scope_len).as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = std::panic::catch_unwind(\
+        std::panic::AssertUnwindSafe(|| -> cannolib::ControlFlow {{\n",
+        result_local).as_bytes()).unwrap();
+
+    with_block(Block::Closure, || {
+        if let Some(ref target) = *optional_vars {
+            unpack_values(outfile, indent + 1, false, &enter_local, target)?;
+        }
+        rec_output_with_items(outfile, indent + 1, iter, body)
+    })?;
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}));\n".as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = match {} {{\n", flow_local,
+        result_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Ok(flow) => {\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("{}.call(vec![cannolib::Value::None, \
+        cannolib::Value::None, cannolib::Value::None], \
+        std::collections::HashMap::new());\n", exit_local)
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all("flow\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("},\n".as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("Err(payload) => {\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("cannoli_scope_list.truncate({});\n", scope_len)
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannolib::exception_from_panic(\
+        payload);\n", exc_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all(format!("if {}.call(vec![{}.get_attr(\"__class__\"), \
+        {}.clone(), cannolib::Value::None], \
+        std::collections::HashMap::new()).to_bool() {{\n", exit_local,
+        exc_local, exc_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 3).as_bytes()).unwrap();
+    outfile.write_all("cannolib::ControlFlow::Next\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all("} else {\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 3).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::reraise({})\n", exc_local)
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 2).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("};\n".as_bytes()).unwrap();
+
+    output_flow_dispatch(outfile, indent, &flow_local)
+}
+
+/// Outputs a match on a `cannolib::ControlFlow` that continues the control
+/// flow a closure was left with in the enclosing block.
+fn output_flow_dispatch(outfile: &mut File, indent: usize, flow_local: &Local)
+    -> Result<(), CompilerError> {
+    let return_str = match return_stmt("val") {
+        Some(return_str) => return_str,
+        None => "unreachable!()".to_string()
+    };
+    let break_str = match loop_exit_stmt("break", "Break") {
+        Some(break_str) => break_str,
+        None => "unreachable!()".to_string()
+    };
+    let continue_str = match loop_exit_stmt("continue", "Continue") {
+        Some(continue_str) => continue_str,
+        None => "unreachable!()".to_string()
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("match {} {{\n", flow_local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("cannolib::ControlFlow::Next => (),\n".as_bytes())
+        .unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::ControlFlow::Return(val) => \
+        {{ {} }},\n", return_str).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::ControlFlow::Break => {{ {} }},\n",
+        break_str).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all(format!("cannolib::ControlFlow::Continue => {{ {} }}\n",
+        continue_str).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    Ok(())
+}
+
 fn output_stmt_import(outfile: &mut File, indent: usize, stmt: &Statement)
     -> Result<(), CompilerError> {
     let names = match *stmt {
@@ -589,9 +1614,10 @@ fn output_stmt_import(outfile: &mut File, indent: usize, stmt: &Statement)
 
         if BUILTIN_MODS.contains(&name[..]) {
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-            outfile.write(format!("cannoli_scope_list.last_mut().unwrap()\
-                .borrow_mut().insert(\"{}\".to_string(), cannolib::builtin::{}\
-                ::import_module());\n", alias, name).as_bytes()).unwrap();
+            outfile.write(format!("{}.insert(\"{}\".to_string(), \
+                cannolib::builtin::{}::import_module());\n",
+                output_name_scope(false, alias), util::escape(alias), name)
+                .as_bytes()).unwrap();
             return Ok(())
         }
 
@@ -601,9 +1627,9 @@ fn output_stmt_import(outfile: &mut File, indent: usize, stmt: &Statement)
         outfile.write(format!("use {};\n", name)
             .as_bytes()).unwrap();
         outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-        outfile.write(format!("cannoli_scope_list.last_mut().unwrap()\
-            .borrow_mut().insert(\"{}\".to_string(), {}::import_module());\n",
-            alias, name).as_bytes()).unwrap();
+        outfile.write(format!("{}.insert(\"{}\".to_string(), \
+            {}::import_module());\n", output_name_scope(false, alias),
+            util::escape(alias), name).as_bytes()).unwrap();
     }
 
     outfile.flush().unwrap();
@@ -637,40 +1663,68 @@ fn output_stmt_import_from(outfile: &mut File, indent: usize, stmt: &Statement)
         unimplemented!()
     };
 
+    // Names are only known at run time, so only a module can import them all
+    if wildcard_present && !SCOPE_STACK.lock().unwrap().is_empty() {
+        return Err(CompilerError::SyntaxError("import * only allowed at \
+            module level".to_string()))
+    }
+
     queue_module(mod_name);
 
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write(format!("use {};\n", mod_name)
         .as_bytes()).unwrap();
-    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write(format!("cannoli_scope_list.last_mut().unwrap()\
-        .borrow_mut().extend(cannolib::split_object({}::import_module(), ",
-        mod_name).as_bytes()).unwrap();
 
-    let mut members_arg = String::new();
     if wildcard_present {
-        members_arg.push_str("None");
-    } else {
-        members_arg.push_str("Some(vec![");
+        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+        outfile.write(format!("cannoli_scope_list.last_mut().unwrap()\
+            .borrow_mut().extend(cannolib::split_object({}::import_module(), \
+            None));\n", mod_name).as_bytes()).unwrap();
+        return Ok(())
+    }
 
-        for name in names.iter() {
-            // TODO check if '*' is used and throw and error at this point
-            let (name, asname) = match *name {
-                Alias::Alias { ref name, ref asname } => (name, asname)
-            };
-            let alias = match *asname {
-                Some(ref alias) => alias,
-                None => name
-            };
+    let members_local = Local::new();
+    let mut members_arg = String::new();
+    let mut aliases = vec![];
+    for name in names.iter() {
+        // TODO check if '*' is used and throw and error at this point
+        let (name, asname) = match *name {
+            Alias::Alias { ref name, ref asname } => (name, asname)
+        };
+        let alias = match *asname {
+            Some(ref alias) => alias,
+            None => name
+        };
 
-            members_arg.push_str(&format!("(\"{}\".to_string(), \"{}\"\
-                .to_string()),", name, alias));
-        }
-        members_arg.pop();
-        members_arg.push_str("])");
+        members_arg.push_str(&format!("(\"{}\".to_string(), \"{}\"\
+            .to_string()),", util::escape(name), util::escape(alias)));
+        aliases.push(alias);
+    }
+    members_arg.pop();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write(format!("let mut {} = cannolib::split_object(\
+        {}::import_module(), Some(vec![{}]));\n", members_local, mod_name,
+        members_arg).as_bytes()).unwrap();
+
+    // Each name is bound like an assignment, `global` and `nonlocal` apply
+    for alias in aliases {
+        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+        outfile.write(format!("{}.insert(\"{}\".to_string(), \
+            {}.remove(\"{}\").unwrap());\n", output_name_scope(false, alias),
+            util::escape(alias), members_local, util::escape(alias))
+            .as_bytes()).unwrap();
     }
+    Ok(())
+}
 
-    outfile.write_all(format!("{}));\n", members_arg).as_bytes()).unwrap();
+/// Declarations only affect the scope analysis, there's nothing to output
+/// beyond checking that `nonlocal` isn't used at module level
+fn output_stmt_nonlocal() -> Result<(), CompilerError> {
+    if SCOPE_STACK.lock().unwrap().is_empty() {
+        return Err(CompilerError::SyntaxError("nonlocal declaration not \
+            allowed at module level".to_string()))
+    }
     Ok(())
 }
 
@@ -685,6 +1739,32 @@ fn output_stmt_expr(outfile: &mut File, indent: usize, stmt: &Statement)
     Ok(())
 }
 
+fn output_stmt_break(outfile: &mut File, indent: usize)
+    -> Result<(), CompilerError> {
+    let break_str = match loop_exit_stmt("break", "Break") {
+        Some(break_str) => break_str,
+        None => return Err(CompilerError::SyntaxError("'break' outside loop"
+            .to_string()))
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("{}\n", break_str).as_bytes()).unwrap();
+    Ok(())
+}
+
+fn output_stmt_continue(outfile: &mut File, indent: usize)
+    -> Result<(), CompilerError> {
+    let continue_str = match loop_exit_stmt("continue", "Continue") {
+        Some(continue_str) => continue_str,
+        None => return Err(CompilerError::SyntaxError("'continue' not \
+            properly in loop".to_string()))
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("{}\n", continue_str).as_bytes()).unwrap();
+    Ok(())
+}
+
 /// Outputs an expression always yielding a cannolib::Value. This value is
 /// stored into a Local, this is done to avoid borrowing conflicts and should
 /// be mitigated by the optimizer (copy propagation).
@@ -700,22 +1780,31 @@ fn output_expr(outfile: &mut File, indent: usize, expr: &Expression)
         Expression::BinOp { .. } => output_expr_binop(outfile, indent, expr),
         Expression::UnaryOp { .. } =>
             output_expr_unaryop(outfile, indent, expr),
-        Expression::Lambda { .. } => unimplemented!(),
+        Expression::Lambda { .. } => output_expr_lambda(outfile, indent, expr),
         Expression::If { .. } => output_expr_if(outfile, indent, expr),
-        Expression::Dict { .. } => unimplemented!(),
-        Expression::Set { .. } => unimplemented!(),
+        Expression::Dict { .. } => output_expr_dict(outfile, indent, expr),
+        Expression::Set { .. } => output_expr_set(outfile, indent, expr),
         Expression::ListComp { .. } =>
             output_expr_listcomp(outfile, indent, expr),
-        Expression::SetComp { .. } => unimplemented!(),
-        Expression::DictComp { .. } => unimplemented!(),
-        Expression::Generator { .. } => unimplemented!(),
+        Expression::SetComp { .. } =>
+            output_expr_setcomp(outfile, indent, expr),
+        Expression::DictComp { .. } =>
+            output_expr_dictcomp(outfile, indent, expr),
+        Expression::Generator { .. } =>
+            output_expr_generator(outfile, indent, expr),
         Expression::None => unimplemented!(),
-        Expression::Yield { .. } => unimplemented!(),
-        Expression::YieldFrom { .. } => unimplemented!(),
+        Expression::Yield { .. } => output_expr_yield(outfile, indent, expr),
+        Expression::YieldFrom { .. } =>
+            output_expr_yield_from(outfile, indent, expr),
         Expression::Compare { .. } => output_expr_cmp(outfile, indent, expr),
         Expression::Call { .. } => output_expr_call(outfile, indent, expr),
         Expression::Num { ref n }  => output_expr_num(outfile, indent, n),
         Expression::Str { ref s }  => output_expr_str(outfile, indent, s),
+        Expression::FormattedValue { .. } =>
+            output_expr_formatted_value(outfile, indent, expr),
+        Expression::JoinedStr { ref values } =>
+            output_expr_joined_str(outfile, indent, values),
+        Expression::Bytes { ref s } => output_expr_bytes(outfile, indent, s),
         Expression::NameConstant { ref value } =>
             output_expr_name_const(outfile, indent, value),
         Expression::Ellipsis => unimplemented!(),
@@ -835,76 +1924,309 @@ fn output_expr_unaryop(outfile: &mut File, indent: usize, expr: &Expression)
             output.push_str(&format!("let mut {} = ({}).logical_not();\n",
                 local, operand_local));
         },
-        UnaryOperator::UAdd => unimplemented!(),
+        UnaryOperator::UAdd => {
+            output.push_str(&format!("let mut {} = ({}).positive();\n", local,
+                operand_local));
+        },
         UnaryOperator::USub => {
             output.push_str(&format!("let mut {} = -{};\n", local,
                 operand_local));
         }
     }
 
-    outfile.write_all(output.as_bytes()).unwrap();
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+/// Outputs a lambda as the same closure-based function that `def` produces,
+/// the body expression is the closure's return value.
+fn output_expr_lambda(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let (args, body) = match *expr {
+        Expression::Lambda { ref args, ref body } => (args, body),
+        _ => unreachable!()
+    };
+    let local = Local::new();
+    let signature = output_parameters(outfile, indent, "<lambda>", args)?;
+
+    output_func_header(outfile, indent, &local, &signature)?;
+
+    let is_generator = util::expr_contains_yield(body);
+    let body_indent = if is_generator { indent + 2 } else { indent + 1 };
+    if is_generator {
+        output_generator_header(outfile, indent + 1)?;
+    }
+
+    let body_local = with_scope(Scope::function(args, &vec![])?, || {
+        with_block(Block::Function(first_arg_name(args)), || {
+            output_expr(outfile, body_indent, body)
+        })
+    })?;
+
+    outfile.write(INDENT.repeat(body_indent).as_bytes()).unwrap();
+    outfile.write(format!("{}\n", body_local).as_bytes()).unwrap();
+    if is_generator {
+        outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+        outfile.write("}))\n".as_bytes()).unwrap();
+    }
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write("}));\n".as_bytes()).unwrap();
+
+    Ok(local)
+}
+
+fn output_expr_if(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let (test, body, orelse) = match *expr {
+        Expression::If { ref test, ref body, ref orelse } =>
+            (test, body, orelse),
+        _ => unreachable!()
+    };
+    let local = Local::new();
+    let test_local = output_expr(outfile, indent, test)?;
+    let body_local = output_expr(outfile, indent, body)?;
+    let orelse_local = output_expr(outfile, indent, orelse)?;
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = if ({}).to_bool() {{ {} }} \
+        else {{ {} }};\n", local, test_local, body_local, orelse_local));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_dict(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let (keys, values) = match *expr {
+        Expression::Dict { ref keys, ref values } => (keys, values),
+        _ => unreachable!()
+    };
+    let local = Local::new();
+
+    // Dicts keep insertion order, a later duplicate key updates the value
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str("let mut cannoli_dict_builder = \
+        cannolib::DictType::new();\n");
+
+    for (key, value) in keys.iter().zip(values.iter()) {
+        match *key {
+            // `**mapping` is represented by a `None` key
+            Expression::None => {
+                let value_local = output_expr(outfile, indent, value)?;
+
+                output.push_str(&INDENT.repeat(indent));
+                output.push_str(&format!("cannoli_dict_builder.update({});\n",
+                    value_local));
+            },
+            _ => {
+                let key_local = output_expr(outfile, indent, key)?;
+                let value_local = output_expr(outfile, indent, value)?;
+
+                output.push_str(&INDENT.repeat(indent));
+                output.push_str(&format!("cannoli_dict_builder.insert({}, \
+                    {});\n", key_local, value_local));
+            }
+        }
+    }
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = cannolib::Value::Dict(\
+        std::rc::Rc::new(std::cell::RefCell::new(cannoli_dict_builder)));\n",
+        local));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_set(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let elts = match *expr {
+        Expression::Set { ref elts } => elts,
+        _ => unreachable!()
+    };
+    let local = Local::new();
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str("let mut cannoli_set_builder = \
+        cannolib::SetType::new();\n");
+
+    for elt in elts.iter() {
+        match *elt {
+            Expression::Starred { ref value, .. } => {
+                let seq_local = output_expr(outfile, indent, value)?;
+
+                output.push_str(&INDENT.repeat(indent));
+                output.push_str(&format!("cannoli_set_builder.extend(\
+                    {}.clone_seq());\n", seq_local));
+            },
+            _ => {
+                let elt_local = output_expr(outfile, indent, elt)?;
+
+                output.push_str(&INDENT.repeat(indent));
+                output.push_str(&format!("cannoli_set_builder.insert({});\n",
+                    elt_local));
+            }
+        }
+    }
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = cannolib::Value::Set(\
+        std::rc::Rc::new(std::cell::RefCell::new(cannoli_set_builder)));\n",
+        local));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_listcomp(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let (elt, generators) = match *expr {
+        Expression::ListComp { ref elt, ref generators } => (elt, generators),
+        _ => unreachable!()
+    };
+    let local = Local::new();
+    let list_local = output_comp(outfile, indent, "vec![]", "push",
+        &vec![&**elt], generators, None)?;
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = cannolib::Value::List(\
+        std::rc::Rc::new(std::cell::RefCell::new(cannolib::ListType::new(\
+        {}))));\n", local, list_local));
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str("cannoli_scope_list.pop();\n");
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_setcomp(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let (elt, generators) = match *expr {
+        Expression::SetComp { ref elt, ref generators } => (elt, generators),
+        _ => unreachable!()
+    };
+    let local = Local::new();
+    let set_local = output_comp(outfile, indent, "cannolib::SetType::new()",
+        "insert", &vec![&**elt], generators, None)?;
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = cannolib::Value::Set(\
+        std::rc::Rc::new(std::cell::RefCell::new({})));\n", local,
+        set_local));
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str("cannoli_scope_list.pop();\n");
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+/// Outputs a generator expression as a generator whose closure runs the
+/// comprehension loops, yielding each element.
+fn output_expr_generator(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let (elt, generators) = match *expr {
+        Expression::Generator { ref elt, ref generators } => (elt, generators),
+        _ => unreachable!()
+    };
+    let local = Local::new();
+    let outer_iter = Local::new();
+
+    // The outermost iterable is evaluated in the enclosing scope when the
+    // generator is created, its iterator is moved into the generator
+    let outer_seq = match generators.first() {
+        Some(&Comprehension::Comprehension { ref iter, .. }) =>
+            output_expr(outfile, indent, iter)?,
+        None => unreachable!()
+    };
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannolib::iter({});\n",
+        outer_iter, outer_seq).as_bytes()).unwrap();
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write("let move_scope = cannoli_scope_list.clone();\n"
+        .as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write(format!("let mut {} = cannolib::Value::Generator(\
+        cannolib::GeneratorType::new(move |cannoli_yielder: cannolib::Yielder| \
+        -> cannolib::Value {{\n", local).as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write("let mut cannoli_scope_list = move_scope;\n"
+        .as_bytes()).unwrap();
+
+    with_block(Block::Function(None), || {
+        output_comp(outfile, indent + 1, "cannoli_yielder", "send",
+            &vec![&**elt], generators, Some(&outer_iter))
+    })?;
+
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write("cannolib::Value::None\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write("}));\n".as_bytes()).unwrap();
+
     Ok(local)
 }
 
-fn output_expr_if(outfile: &mut File, indent: usize, expr: &Expression)
+fn output_expr_dictcomp(outfile: &mut File, indent: usize, expr: &Expression)
     -> Result<Local, CompilerError> {
     let mut output = String::new();
-    let (test, body, orelse) = match *expr {
-        Expression::If { ref test, ref body, ref orelse } =>
-            (test, body, orelse),
+    let (key, value, generators) = match *expr {
+        Expression::DictComp { ref key, ref value, ref generators } =>
+            (key, value, generators),
         _ => unreachable!()
     };
     let local = Local::new();
-    let test_local = output_expr(outfile, indent, test)?;
-    let body_local = output_expr(outfile, indent, body)?;
-    let orelse_local = output_expr(outfile, indent, orelse)?;
+    let dict_local = output_comp(outfile, indent, "cannolib::DictType::new()",
+        "insert", &vec![&**key, &**value], generators, None)?;
 
     output.push_str(&INDENT.repeat(indent));
-    output.push_str(&format!("let mut {} = if ({}).to_bool() {{ {} }} \
-        else {{ {} }};\n", local, test_local, body_local, orelse_local));
+    output.push_str(&format!("let mut {} = cannolib::Value::Dict(\
+        std::rc::Rc::new(std::cell::RefCell::new({})));\n", local,
+        dict_local));
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str("cannoli_scope_list.pop();\n");
 
     outfile.write_all(output.as_bytes()).unwrap();
     Ok(local)
 }
 
-fn output_expr_listcomp(outfile: &mut File, indent: usize, expr: &Expression)
-    -> Result<Local, CompilerError> {
-    let mut output = String::new();
-    let (elt, generators) = match *expr {
-        Expression::ListComp { ref elt, ref generators } => (elt, generators),
-        _ => unreachable!()
-    };
-    let local = Local::new();
-    let list_local = Local::new();
-
-    // Isolate the list comprehension inorder to ensure targets don't get
-    // mapped to the current scope list, then start building output list
+/// Outputs the loops of a comprehension into a builder initialized with
+/// `init`, returning the builder's local. The comprehension gets its own scope
+/// that the caller pops once the builder has been wrapped into a value. The
+/// iterator of the outermost loop is passed in if it was already created.
+fn output_comp(outfile: &mut File, indent: usize, init: &str, add: &str,
+    elts: &Vec<&Expression>, generators: &Vec<Comprehension>,
+    outer_iter: Option<&Local>) -> Result<Local, CompilerError> {
+    let builder_local = Local::new();
+
+    // Isolate the comprehension inorder to ensure targets don't get mapped to
+    // the current scope list, then start building output
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write_all("cannoli_scope_list.push(std::rc::Rc::new(\
         std::cell::RefCell::new(std::collections::HashMap::new())));\n"
         .as_bytes()).unwrap();
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all(&format!("let mut {} = vec![];\n", list_local)
+    outfile.write_all(&format!("let mut {} = {};\n", builder_local, init)
         .as_bytes()).unwrap();
 
     let gen_iter = generators.iter().peekable();
-    output_nested_listcomp(outfile, indent, &list_local, elt, gen_iter)?;
-
-    output.push_str(&INDENT.repeat(indent));
-    output.push_str(&format!("let mut {} = cannolib::Value::List(\
-        std::rc::Rc::new(std::cell::RefCell::new(cannolib::ListType::new(\
-        {}))));\n", local, list_local));
-    output.push_str(&INDENT.repeat(indent));
-    output.push_str("cannoli_scope_list.pop();\n");
+    with_scope(Scope::comprehension(), || {
+        output_nested_comp(outfile, indent, &builder_local, add, elts,
+            gen_iter, outer_iter)
+    })?;
 
-    outfile.write_all(output.as_bytes()).unwrap();
-    Ok(local)
+    Ok(builder_local)
 }
 
-// Tail recurse on nested fors in a list comprehension this was done to print
+// Tail recurse on nested fors in a comprehension this was done to print
 // matching brackets in a much cleaner way
-fn output_nested_listcomp(outfile: &mut File, indent: usize, list_local: &Local,
-    elt: &Expression, mut gen_iter: Peekable<Iter<Comprehension>>)
+fn output_nested_comp(outfile: &mut File, indent: usize, builder_local: &Local,
+    add: &str, elts: &Vec<&Expression>,
+    mut gen_iter: Peekable<Iter<Comprehension>>, outer_iter: Option<&Local>)
     -> Result<(), CompilerError> {
     let comp = match gen_iter.next() {
         Some(comp) => comp,
@@ -914,75 +2236,117 @@ fn output_nested_listcomp(outfile: &mut File, indent: usize, list_local: &Local,
         Comprehension::Comprehension { ref target, ref iter, ref ifs} =>
             (target, iter, ifs)
     };
-    let iter_local = Local::new();
-    let seq_local = output_expr(outfile, indent, iter)?;
+    let loop_label = Local::new();
+    let iter_local = match outer_iter {
+        Some(iter_local) => iter_local.clone(),
+        None => {
+            let iter_local = Local::new();
+            let seq_local = output_expr(outfile, indent, iter)?;
 
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            outfile.write_all(format!("let mut {} = cannolib::iter({});\n",
+                iter_local, seq_local).as_bytes()).unwrap();
+            iter_local
+        }
+    };
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all(format!("let mut {} = {}.clone_seq()\
-        .into_iter();\n", iter_local, seq_local).as_bytes()).unwrap();
-    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write_all("loop {\n".as_bytes()).unwrap();
+    outfile.write_all(format!("'{}: loop {{\n", loop_label)
+        .as_bytes()).unwrap();
 
     let next_local = Local::new();
     outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
     outfile.write_all(format!("let mut {} = if let Some(val) = \
-        {}.next() {{ val }} else {{ break }};\n", next_local,
-        iter_local).as_bytes()).unwrap();
-    unpack_values(outfile, indent + 1, None, &next_local, target)?;
+        {}.next() {{ val }} else {{ break '{} }};\n", next_local,
+        iter_local, loop_label).as_bytes()).unwrap();
+    unpack_values(outfile, indent + 1, false, &next_local, target)?;
 
-    let mut conds = vec![];
+    // Each condition is only evaluated if the ones before it passed
     for cond in ifs.iter() {
         let cond_local = output_expr(outfile, indent + 1, cond)?;
-        conds.push(cond_local);
-    }
 
-    if !conds.is_empty() {
         outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-        outfile.write("if ".as_bytes()).unwrap();
-
-        let mut cond_iter = conds.iter().peekable();
-        loop {
-            let cond = match cond_iter.next() {
-                Some(cond) => cond,
-                None => break
-            };
-            outfile.write(format!("({}).to_bool()", cond)
-                .as_bytes()).unwrap();
-
-            if let Some(_) = cond_iter.peek() {
-                outfile.write(" && ".as_bytes()).unwrap();
-            }
-        }
-
-        outfile.write_all(" {\n".as_bytes()).unwrap();
+        outfile.write_all(format!("if !({}).to_bool() {{ continue '{} }}\n",
+            cond_local, loop_label).as_bytes()).unwrap();
     }
 
-    let cond_indent = if conds.is_empty() { indent + 1 } else { indent + 2 };
-    // For the most nested element we want to append the 'elt'
+    // For the most nested element we want to add the 'elts'
     if let None = gen_iter.peek() {
-        let elt_local = output_expr(outfile, cond_indent, elt)?;
+        let mut elts_arg = String::new();
 
-        outfile.write(INDENT.repeat(cond_indent).as_bytes()).unwrap();
-        outfile.write(format!("{}.push({});\n", list_local, elt_local)
+        for elt in elts.iter() {
+            let elt_local = output_expr(outfile, indent + 1, elt)?;
+            elts_arg.push_str(&format!("{}, ", elt_local));
+        }
+        elts_arg.pop();
+        elts_arg.pop();
+
+        outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+        outfile.write(format!("{}.{}({});\n", builder_local, add, elts_arg)
             .as_bytes()).unwrap();
     }
 
     // recurse before we output closing brackets
-    output_nested_listcomp(outfile, cond_indent, list_local, elt, gen_iter)?;
+    output_nested_comp(outfile, indent + 1, builder_local, add, elts,
+        gen_iter, None)?;
 
-    if !conds.is_empty() {
-        outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
-        outfile.write("}\n".as_bytes()).unwrap();
-    }
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
     outfile.write("}\n".as_bytes()).unwrap();
 
     Ok(())
 }
 
+/// Suspends the generator with the yielded value, the result is the value
+/// sent into the generator when it's resumed.
+fn output_expr_yield(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let value = match *expr {
+        Expression::Yield { ref value } => value,
+        _ => unreachable!()
+    };
+    let local = Local::new();
+
+    if !in_function() {
+        return Err(CompilerError::SyntaxError("'yield' outside function"
+            .to_string()))
+    }
+
+    let value_str = match *value {
+        Some(ref value) => output_expr(outfile, indent, value)?.get_label(),
+        None => "cannolib::Value::None".to_string()
+    };
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannoli_yielder.send({});\n",
+        local, value_str).as_bytes()).unwrap();
+    Ok(local)
+}
+
+/// Delegates to the iterable until it's exhausted, the result is the value
+/// returned by a delegated generator.
+fn output_expr_yield_from(outfile: &mut File, indent: usize, expr: &Expression)
+    -> Result<Local, CompilerError> {
+    let value = match *expr {
+        Expression::YieldFrom { ref value } => value,
+        _ => unreachable!()
+    };
+    let local = Local::new();
+
+    if !in_function() {
+        return Err(CompilerError::SyntaxError("'yield' outside function"
+            .to_string()))
+    }
+
+    let value_local = output_expr(outfile, indent, value)?;
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannoli_yielder.delegate({});\n",
+        local, value_local).as_bytes()).unwrap();
+    Ok(local)
+}
+
+/// Outputs a comparison as nested if-expressions, each comparator is evaluated
+/// once and only if the links before it were true.
 fn output_expr_cmp(outfile: &mut File, indent: usize, expr: &Expression)
     -> Result<Local, CompilerError> {
-    let mut output = String::new();
     let (left, ops, comparators) = match *expr {
         Expression::Compare { ref left, ref ops, ref comparators } =>
             (left, ops, comparators),
@@ -991,32 +2355,49 @@ fn output_expr_cmp(outfile: &mut File, indent: usize, expr: &Expression)
     let local = Local::new();
     let left_local = output_expr(outfile, indent, left)?;
 
-    output.push_str(&INDENT.repeat(indent));
-    output.push_str(&format!("let mut {} = cannolib::Value::Bool(({}", local,
-        left_local));
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = {{\n", local).as_bytes()).unwrap();
 
-    let mut cmp_iter = ops.iter().zip(comparators.iter()).peekable();
-    loop {
-        match cmp_iter.next() {
-            Some((op, comparator)) => {
-                let cmp_local = output_expr(outfile, indent, comparator)?;
-                output.push_str(&format!("{})", output_cmp_operator(op,
-                    &cmp_local)?));
-
-                if let Some(_) = cmp_iter.peek() {
-                    let cmp_local = output_expr(outfile, indent, comparator)?;
-                    output.push_str(&format!(" && ({}", cmp_local));
-                }
-            },
-            None => break
-        }
-    }
-    output.push_str(");\n");
+    let cmp_iter = ops.iter().zip(comparators.iter()).peekable();
+    rec_output_cmp(outfile, indent + 1, &left_local, cmp_iter)?;
 
-    outfile.write_all(output.as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("};\n".as_bytes()).unwrap();
     Ok(local)
 }
 
+fn rec_output_cmp(outfile: &mut File, indent: usize, left_local: &Local,
+    mut cmp_iter: Peekable<Zip<Iter<CmpOperator>, Iter<Expression>>>)
+    -> Result<(), CompilerError> {
+    let (op, comparator) = match cmp_iter.next() {
+        Some(link) => link,
+        None => unreachable!()
+    };
+    let cmp_local = output_expr(outfile, indent, comparator)?;
+    let cmp_str = format!("({}{})", left_local,
+        output_cmp_operator(op, &cmp_local)?);
+
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    if cmp_iter.peek().is_none() {
+        outfile.write_all(format!("cannolib::Value::Bool({})\n", cmp_str)
+            .as_bytes()).unwrap();
+        return Ok(())
+    }
+
+    // The comparator is the left operand of the next link
+    outfile.write_all(format!("if {} {{\n", cmp_str).as_bytes()).unwrap();
+    rec_output_cmp(outfile, indent + 1, &cmp_local, cmp_iter)?;
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("} else {\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent + 1).as_bytes()).unwrap();
+    outfile.write_all("cannolib::Value::Bool(false)\n".as_bytes()).unwrap();
+    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+    outfile.write_all("}\n".as_bytes()).unwrap();
+    Ok(())
+}
+
+/// Outputs a call, the function is evaluated first followed by the positional
+/// and then the keyword arguments.
 fn output_expr_call(outfile: &mut File, indent: usize, expr: &Expression)
     -> Result<Local, CompilerError> {
     let mut output = String::new();
@@ -1027,78 +2408,150 @@ fn output_expr_call(outfile: &mut File, indent: usize, expr: &Expression)
     };
     let local = Local::new();
 
-    output.push_str(&INDENT.repeat(indent));
-    output.push_str("let mut kwargs = std::collections::HashMap::new();\n");
-    for keyword in keywords.iter() {
-        let (arg, value) = match *keyword {
-            Keyword::Keyword { ref arg, ref value } => (arg.clone(), value)
-        };
-        let kw_local = output_expr(outfile, indent, value)?;
-
-        output.push_str(&INDENT.repeat(indent));
-        output.push_str(&format!("kwargs.insert(\"{}\".to_string(), {});\n",
-            arg.unwrap(), kw_local));
-    }
-
-    output.push_str(&INDENT.repeat(indent));
-    match **func {
+    let call_str = match **func {
         Expression::Attribute { ref value, ref attr, .. } => {
             let value_local = output_expr(outfile, indent, value)?;
-            output.push_str(&format!("let mut {} = cannolib::call_member({}, \
-                \"{}\", vec![", local, value_local, attr));
+            format!("cannolib::call_member({}, \"{}\", ", value_local,
+                util::escape(attr))
         },
         _ => {
             let func_local = output_expr(outfile, indent, func)?;
-            output.push_str(&format!("let mut {} = {}.call(vec![",
-                local, func_local));
+            format!("{}.call(", func_local)
         }
+    };
+    let args_str = match (&**func, super_self_name()) {
+        (&Expression::Name { ref id, .. }, Some(ref self_name))
+            if id == "super" && args.is_empty() && keywords.is_empty() => {
+            // Zero argument form, pass `__class__` and the method's `self`
+            format!("vec![cannolib::lookup_value(&cannoli_scope_list, \
+                \"__class__\"), cannolib::lookup_value(&cannoli_scope_list, \
+                \"{}\")]", util::escape(self_name))
+        },
+        _ => output_call_args(outfile, indent, args, &mut output)?
+    };
+    let kwargs_local = output_keywords(outfile, indent, keywords,
+        &mut output)?;
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = {}{}, {});\n", local, call_str,
+        args_str, kwargs_local));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+/// Outputs the positional arguments of a call, returning the Rust expression
+/// for the argument vector. Starred arguments are spliced in at run time.
+fn output_call_args(outfile: &mut File, indent: usize, args: &Vec<Expression>,
+    output: &mut String) -> Result<String, CompilerError> {
+    let starred = args.iter().any(|arg| match *arg {
+        Expression::Starred { .. } => true,
+        _ => false
+    });
+
+    if !starred {
+        let mut args_str = String::new();
+
+        for arg in args.iter() {
+            let arg_local = output_expr(outfile, indent, arg)?;
+            args_str.push_str(&format!("{}, ", arg_local));
+        }
+        args_str.pop();
+        args_str.pop();
+
+        return Ok(format!("vec![{}]", args_str))
     }
 
-    let mut args_iter = args.iter().peekable();
-    loop {
-        match args_iter.next() {
-            Some(expr) => {
-                let expr_local = output_expr(outfile, indent, expr)?;
-                output.push_str(&format!("{}", expr_local));
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str("let mut cannoli_args_builder = Vec::new();\n");
+    for arg in args.iter() {
+        match *arg {
+            Expression::Starred { ref value, .. } => {
+                let seq_local = output_expr(outfile, indent, value)?;
 
-                if let Some(_) = args_iter.peek() {
-                    output.push_str(", ");
-                }
+                output.push_str(&INDENT.repeat(indent));
+                output.push_str(&format!("cannoli_args_builder.extend(\
+                    {}.clone_seq());\n", seq_local));
             },
-            None => break
+            _ => {
+                let arg_local = output_expr(outfile, indent, arg)?;
+
+                output.push_str(&INDENT.repeat(indent));
+                output.push_str(&format!("cannoli_args_builder.push({});\n",
+                    arg_local));
+            }
         }
     }
-    output.push_str("], kwargs);\n");
 
-    outfile.write_all(output.as_bytes()).unwrap();
-    Ok(local)
+    Ok("cannoli_args_builder".to_string())
+}
+
+/// Outputs the keyword arguments of a call into a new map, returning its
+/// local. The `**` mappings are merged in at run time, keywords that appear
+/// after one are inserted with a check since they may already be present.
+fn output_keywords(outfile: &mut File, indent: usize, keywords: &Vec<Keyword>,
+    output: &mut String) -> Result<Local, CompilerError> {
+    let mut names = HashSet::new();
+    let mut merged = false;
+    let kwargs_local = Local::new();
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = std::collections::HashMap::new();\n",
+        kwargs_local));
+    for keyword in keywords.iter() {
+        let (arg, value) = match *keyword {
+            Keyword::Keyword { ref arg, ref value } => (arg, value)
+        };
+        let kw_local = output_expr(outfile, indent, value)?;
+
+        output.push_str(&INDENT.repeat(indent));
+        match *arg {
+            Some(ref arg) => {
+                if !names.insert(arg.clone()) {
+                    return Err(CompilerError::SyntaxError(format!("keyword \
+                        argument repeated: {}", arg)))
+                }
+
+                if merged {
+                    output.push_str(&format!("cannolib::insert_kwarg(\
+                        &mut {}, \"{}\".to_string(), {});\n", kwargs_local,
+                        util::escape(arg), kw_local));
+                } else {
+                    output.push_str(&format!("{}.insert(\"{}\".to_string(), \
+                        {});\n", kwargs_local, util::escape(arg), kw_local));
+                }
+            },
+            None => {
+                output.push_str(&format!("cannolib::merge_kwargs(&mut {}, \
+                    {});\n", kwargs_local, kw_local));
+                merged = true;
+            }
+        }
+    }
+    Ok(kwargs_local)
 }
 
 fn output_expr_num(outfile: &mut File, indent: usize, num: &Number)
     -> Result<Local, CompilerError> {
     let mut output = String::new();
     let out_str = match *num {
-        Number::DecInteger(ref s) => {
-            format!("cannolib::Value::Number(\
-                cannolib::NumericType::Integer({}))", s)
-        },
-        Number::BinInteger(ref s) => {
-            format!("cannolib::Value::Number(\
-                cannolib::NumericType::Integer({}))", s)
-        },
-        Number::OctInteger(ref s) => {
-            format!("cannolib::Value::Number(\
-                cannolib::NumericType::Integer({}))", s)
-        },
-        Number::HexInteger(ref s) => {
-            format!("cannolib::Value::Number(\
-                cannolib::NumericType::Integer({}))", s)
-        },
+        // Radix prefixes are stripped, their case doesn't matter
+        Number::DecInteger(ref s) =>
+            output_integer(&util::canonical_integer(s, 10)?)?,
+        Number::BinInteger(ref s) =>
+            output_integer(&util::canonical_integer(&s[2..], 2)?)?,
+        Number::OctInteger(ref s) =>
+            output_integer(&util::canonical_integer(&s[2..], 8)?)?,
+        Number::HexInteger(ref s) =>
+            output_integer(&util::canonical_integer(&s[2..], 16)?)?,
         Number::Float(ref s) => {
             format!("cannolib::Value::Number(\
-                cannolib::NumericType::Float({}))", s)
+                cannolib::NumericType::Float({}))", util::float_literal(s)?)
         },
-        Number::Imaginary(_) => unimplemented!()
+        // cannolib has no complex numeric type to build these with
+        Number::Imaginary(_) =>
+            return Err(CompilerError::NotImplementedError(
+                "imaginary literals".to_string()))
     };
     let local = Local::new();
 
@@ -1109,10 +2562,85 @@ fn output_expr_num(outfile: &mut File, indent: usize, num: &Number)
     Ok(local)
 }
 
+// cannolib's `NumericType::Integer` wraps an `i32` and it has no big integer
+// type, literals outside of that range can't be represented
+fn output_integer(canonical: &str) -> Result<String, CompilerError> {
+    match canonical.parse::<i32>() {
+        Ok(_) => Ok(format!("cannolib::Value::Number(\
+            cannolib::NumericType::Integer({}))", canonical)),
+        Err(_) => Err(CompilerError::NotImplementedError("integer literals \
+            outside of the 32 bit range".to_string()))
+    }
+}
+
 fn output_expr_str(outfile: &mut File, indent: usize, string: &String)
     -> Result<Local, CompilerError> {
     let mut output = String::new();
-    let out_str = format!("cannolib::Value::Str(\"{}\".to_string())", string);
+    let out_str = format!("cannolib::Value::Str(\"{}\".to_string())",
+        util::escape(string));
+    let local = Local::new();
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = {};\n", local, out_str));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_formatted_value(outfile: &mut File, indent: usize,
+    expr: &Expression) -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let (value, conversion, format_spec) = match *expr {
+        Expression::FormattedValue { ref value, ref conversion,
+            ref format_spec } => (value, conversion, format_spec),
+        _ => unreachable!()
+    };
+    let value_local = output_expr(outfile, indent, value)?;
+    let spec = match *format_spec {
+        Some(ref format_spec) => output_expr(outfile, indent, format_spec)?
+            .to_string(),
+        None => "cannolib::Value::Str(String::new())".to_string()
+    };
+    let conversion = match *conversion {
+        Some(Conversion::Str) => "Some('s')",
+        Some(Conversion::Repr) => "Some('r')",
+        Some(Conversion::Ascii) => "Some('a')",
+        None => "None"
+    };
+    let local = Local::new();
+
+    // Applies the conversion, then `format(value, spec)`
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = cannolib::format_value(&{}, {}, \
+        &{});\n", local, value_local, conversion, spec));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_joined_str(outfile: &mut File, indent: usize,
+    values: &Vec<Expression>) -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let mut value_locals = vec![];
+
+    for value in values.iter() {
+        value_locals.push(output_expr(outfile, indent, value)?.to_string());
+    }
+    let local = Local::new();
+
+    output.push_str(&INDENT.repeat(indent));
+    output.push_str(&format!("let mut {} = cannolib::build_string(vec![{}]);\n",
+        local, value_locals.join(", ")));
+
+    outfile.write_all(output.as_bytes()).unwrap();
+    Ok(local)
+}
+
+fn output_expr_bytes(outfile: &mut File, indent: usize, bytes: &Vec<u8>)
+    -> Result<Local, CompilerError> {
+    let mut output = String::new();
+    let out_str = format!("cannolib::Value::Bytes(b\"{}\".to_vec())",
+        util::escape_bytes(bytes));
     let local = Local::new();
 
     output.push_str(&INDENT.repeat(indent));
@@ -1152,7 +2680,7 @@ fn output_expr_attr(outfile: &mut File, indent: usize, expr: &Expression)
 
     output.push_str(&INDENT.repeat(indent));
     output.push_str(&format!("let mut {} = {}.get_attr(\"{}\");\n", local,
-        value_local, attr));
+        value_local, util::escape(attr)));
 
     outfile.write_all(output.as_bytes()).unwrap();
     Ok(local)
@@ -1171,31 +2699,12 @@ fn output_expr_subscript(outfile: &mut File, indent: usize, expr: &Expression)
 
     match **slice {
         Slice::Slice { ref lower, ref upper, ref step } => {
-            let lower_arg = match *lower {
-                Some(ref expr) => {
-                    let expr_local = output_expr(outfile, indent, expr)?;
-                    format!("Some({})", expr_local)
-                },
-                None => "None".to_string()
-            };
-            let upper_arg = match *upper {
-                Some(ref expr) => {
-                    let expr_local = output_expr(outfile, indent, expr)?;
-                    format!("Some({})", expr_local)
-                },
-                None => "None".to_string()
-            };
-            let step_arg = match *step {
-                Some(ref expr) => {
-                    let expr_local = output_expr(outfile, indent, expr)?;
-                    format!("Some({})", expr_local)
-                },
-                None => "None".to_string()
-            };
+            let bounds = output_slice_bounds(outfile, indent, lower, upper,
+                step)?;
 
             output.push_str(&INDENT.repeat(indent));
-            output.push_str(&format!("let mut {} = {}.slice({}, {}, {});\n",
-                local, value_local, lower_arg, upper_arg, step_arg));
+            output.push_str(&format!("let mut {} = {}.slice({});\n",
+                local, value_local, bounds));
         },
         Slice::ExtSlice { .. } => unimplemented!(),
         Slice::Index { ref value } => {
@@ -1211,6 +2720,25 @@ fn output_expr_subscript(outfile: &mut File, indent: usize, expr: &Expression)
     Ok(local)
 }
 
+/// Outputs the optional bounds of a slice, returning them as the arguments
+/// for the slicing methods on `cannolib::Value`
+fn output_slice_bounds(outfile: &mut File, indent: usize,
+    lower: &Option<Expression>, upper: &Option<Expression>,
+    step: &Option<Expression>) -> Result<String, CompilerError> {
+    let mut bounds = vec![];
+
+    for bound in [lower, upper, step].iter() {
+        match **bound {
+            Some(ref expr) => {
+                let expr_local = output_expr(outfile, indent, expr)?;
+                bounds.push(format!("Some({})", expr_local));
+            },
+            None => bounds.push("None".to_string())
+        }
+    }
+    Ok(bounds.join(", "))
+}
+
 fn output_expr_name(outfile: &mut File, indent: usize, expr: &Expression)
     -> Result<Local, CompilerError> {
     let mut output = String::new();
@@ -1220,9 +2748,32 @@ fn output_expr_name(outfile: &mut File, indent: usize, expr: &Expression)
     };
     let local = Local::new();
 
+    // `__debug__` is a constant determined by the optimization level
+    if id == "__debug__" {
+        output.push_str(&INDENT.repeat(indent));
+        output.push_str(&format!("let mut {} = cannolib::Value::Bool({});\n",
+            local, debug_mode()));
+        outfile.write_all(output.as_bytes()).unwrap();
+        return Ok(local)
+    }
+
     output.push_str(&INDENT.repeat(indent));
-    output.push_str(&format!("let mut {} = cannolib::lookup_value(\
-        &cannoli_scope_list, \"{}\");\n", local, id));
+    if is_function_local(id) {
+        // An unbound local doesn't fall back to a global of the same name
+        output.push_str(&format!("let mut {} = match cannoli_scope_list\
+            .last().unwrap().borrow().get(\"{}\") {{ Some(value) => \
+            value.clone(), None => {} }};\n", local, util::escape(id),
+            output_unbound_name_error(id)));
+    } else if is_class_local(id) {
+        // A class body reads its own table before the enclosing scopes
+        output.push_str(&format!("let mut {} = match cannoli_object_tbl\
+            .get(\"{}\") {{ Some(value) => value.clone(), None => \
+            cannolib::lookup_value({}, \"{}\") }};\n", local,
+            util::escape(id), output_name_lookup(id), util::escape(id)));
+    } else {
+        output.push_str(&format!("let mut {} = cannolib::lookup_value({}, \
+            \"{}\");\n", local, output_name_lookup(id), util::escape(id)));
+    }
 
     outfile.write_all(output.as_bytes()).unwrap();
     Ok(local)
@@ -1285,42 +2836,94 @@ fn output_expr_tuple(outfile: &mut File, indent: usize, expr: &Expression)
     Ok(local)
 }
 
-fn output_parameters(outfile: &mut File, indent: usize, params: &Arguments)
-    -> Result<(), CompilerError> {
-    let (args, _vararg, _kwonlyargs, _kw_defaults, _kwarg, _defaults) =
+fn first_arg_name(params: &Arguments) -> Option<String> {
+    match *params {
+        Arguments::Arguments { ref args, .. } => {
+            match args.first() {
+                Some(&Arg::Arg { ref arg, .. }) => Some(arg.clone()),
+                None => None
+            }
+        }
+    }
+}
+
+/// Outputs a `cannolib::Signature` describing the parameters, returning the
+/// local it's stored in. Default values are evaluated once here, when the
+/// function is defined, calls bind their arguments with the signature which
+/// raises a `TypeError` if they don't match.
+fn output_parameters(outfile: &mut File, indent: usize, name: &str,
+    params: &Arguments) -> Result<Local, CompilerError> {
+    let (args, vararg, kwonlyargs, kw_defaults, kwarg, defaults) =
     match *params {
         Arguments::Arguments { ref args, ref vararg, ref kwonlyargs,
             ref kw_defaults, ref kwarg, ref defaults } => (args, vararg,
             kwonlyargs, kw_defaults, kwarg, defaults)
     };
+    let local = Local::new();
+
+    let mut defaults_arg = String::new();
+    for default in defaults.iter() {
+        let default_local = output_expr(outfile, indent, default)?;
+        defaults_arg.push_str(&format!("{}, ", default_local));
+    }
+
+    // Keyword-only parameters without a default are paired with `None`
+    let mut kw_defaults_arg = String::new();
+    for default in kw_defaults.iter() {
+        match *default {
+            Expression::None => kw_defaults_arg.push_str("None, "),
+            _ => {
+                let default_local = output_expr(outfile, indent, default)?;
+                kw_defaults_arg.push_str(&format!("Some({}), ",
+                    default_local));
+            }
+        }
+    }
 
     outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-    outfile.write("let mut cannoli_func_args_iter = \
-        cannoli_func_args.into_iter();\n".as_bytes()).unwrap();
+    outfile.write_all(format!("let mut {} = cannolib::Signature::new(\"{}\", \
+        vec![{}], {}, vec![{}], vec![{}], {}, vec![{}]);\n", local,
+        util::escape(name),
+        output_arg_names(args), output_opt_arg_name(vararg),
+        output_arg_names(kwonlyargs), kw_defaults_arg,
+        output_opt_arg_name(kwarg), defaults_arg).as_bytes()).unwrap();
+
+    Ok(local)
+}
+
+fn output_arg_names(args: &Vec<Arg>) -> String {
+    let mut names = String::new();
+
     for arg in args.iter() {
-        let (arg_name, _arg_annotation) = match *arg {
-            Arg::Arg { ref arg, ref annotation } => (arg, annotation)
+        let arg_name = match *arg {
+            Arg::Arg { ref arg, .. } => arg
         };
-        //let mangled_name = util::mangle_name(&arg_name);
-
-        outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
-        outfile.write(format!("cannoli_scope_list.last_mut().unwrap()\
-            .borrow_mut().insert(\"{}\".to_string(), cannoli_func_args_iter\
-            .next().unwrap_or(cannolib::Value::None));\n", arg_name)
-            .as_bytes()).unwrap();
+        names.push_str(&format!("\"{}\".to_string(), ",
+            util::escape(arg_name)));
     }
+    names
+}
 
-    outfile.flush().unwrap();
-    Ok(())
+fn output_opt_arg_name(arg: &Option<Arg>) -> String {
+    match *arg {
+        Some(Arg::Arg { ref arg, .. }) => format!("Some(\"{}\".to_string())",
+            util::escape(arg)),
+        None => "None".to_string()
+    }
 }
 
+// Operators map onto cannolib's `Value` implementations, Python's semantics
+// for `/`, `%` and `**` with negative or mixed operands are up to the runtime.
+// It has no floor division or matrix multiplication to map onto.
 fn output_operator(lft: &Local, op: &Operator, rht: &Local)
     -> Result<String, CompilerError> {
     let op_str = match *op {
         Operator::Add => format!("{} + {}", lft, rht),
         Operator::Sub => format!("{} - {}", lft, rht),
         Operator::Mult => format!("{} * {}", lft, rht),
-        Operator::MatMult => unimplemented!(),
+        Operator::MatMult =>
+            return Err(CompilerError::NotImplementedError(
+                "matrix multiplication".to_string())),
         Operator::Div => format!("{} / {}", lft, rht),
         Operator::Mod => format!("{} % {}", lft, rht),
         Operator::Pow => format!("{}.pow(&{})", lft, rht),
@@ -1329,7 +2932,9 @@ fn output_operator(lft: &Local, op: &Operator, rht: &Local)
         Operator::BitOr => format!("{} | {}", lft, rht),
         Operator::BitXor => format!("{} ^ {}", lft, rht),
         Operator::BitAnd => format!("{} & {}", lft, rht),
-        Operator::FloorDiv => unimplemented!()
+        Operator::FloorDiv =>
+            return Err(CompilerError::NotImplementedError(
+                "floor division".to_string()))
     };
     Ok(op_str)
 }
@@ -1345,66 +2950,103 @@ fn output_cmp_operator(op: &CmpOperator, val: &Local)
         CmpOperator::LE => format!(" <= {}", val),
         CmpOperator::GT => format!(" > {}", val),
         CmpOperator::GE => format!(" >= {}", val),
-        CmpOperator::Is => unimplemented!(),
-        CmpOperator::IsNot => unimplemented!(),
+        CmpOperator::Is => format!(".is(&{})", val),
+        CmpOperator::IsNot => format!(".is_not(&{})", val),
         CmpOperator::In => format!(".contained_in(&{})", val),
         CmpOperator::NotIn => format!(".not_contained_in(&{})", val)
     };
     Ok(op_str)
 }
 
-// TODO add list support when needed, should be just like Tuples
-/// Tail-recursive function that recursively unpacks values.
-fn unpack_values(outfile: &mut File, indent: usize, scope: Option<&str>,
+/// Tail-recursive function that recursively unpacks values. Tuple and list
+/// targets check the number of values at run time, a starred target collects
+/// the values left over by the other targets into a list.
+fn unpack_values(outfile: &mut File, indent: usize, class_scope: bool,
     packed_values: &Local, target: &Expression) -> Result<(), CompilerError> {
-    // Default is cannoli_scope_list
-    let scope = match scope {
-        Some(s) => s,
-        None => "cannoli_scope_list.last_mut().unwrap().borrow_mut()"
-    };
     match *target {
         Expression::Name { ref id, .. } => {
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
             outfile.write_all(format!("{}.insert(\"{}\".to_string(), {});\n",
-                scope, id, packed_values).as_bytes()).unwrap();
+                output_name_scope(class_scope, id), util::escape(id),
+                packed_values)
+                .as_bytes()).unwrap();
         },
         Expression::Attribute { ref value, ref attr, .. } => {
             let base_local = output_expr(outfile, indent, value)?;
             outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
             outfile.write_all(format!("cannolib::attr_assign({}, \"{}\", {}\
-                );\n", base_local, attr, packed_values).as_bytes()).unwrap();
+                );\n", base_local, util::escape(attr), packed_values)
+                .as_bytes()).unwrap();
         },
-        Expression::List { .. } => {
-            unimplemented!()
+        Expression::Subscript { ref value, ref slice, .. } => {
+            let base_local = output_expr(outfile, indent, value)?;
+
+            match **slice {
+                Slice::Slice { ref lower, ref upper, ref step } => {
+                    let bounds = output_slice_bounds(outfile, indent, lower,
+                        upper, step)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("{}.set_slice({}, {});\n",
+                        base_local, bounds, packed_values).as_bytes())
+                        .unwrap();
+                },
+                Slice::ExtSlice { .. } =>
+                    return Err(CompilerError::NotImplementedError(
+                        "extended slices".to_string())),
+                Slice::Index { ref value } => {
+                    let index_local = output_expr(outfile, indent, value)?;
+
+                    outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                    outfile.write_all(format!("{}.set_index({}, {});\n",
+                        base_local, index_local, packed_values).as_bytes())
+                        .unwrap();
+                }
+            }
         },
+        Expression::List { ref elts, .. } |
         Expression::Tuple { ref elts, .. } => {
-            for (ndx, elt) in elts.iter().enumerate() {
-                match *elt {
-                    Expression::Name { ref id, .. } => {
-                        outfile.write(INDENT.repeat(indent).as_bytes())
-                            .unwrap();
-                        outfile.write_all(format!("{}.insert(\"{}\"\
-                            .to_string(), {}.index(cannolib::Value::Number(\
-                            cannolib::NumericType::Integer({}))));\n", scope,
-                            id, packed_values, ndx).as_bytes()).unwrap();
-                    },
-                    Expression::Tuple { .. } => {
-                        let local = Local::new();
-
-                        outfile.write(INDENT.repeat(indent).as_bytes())
-                            .unwrap();
-                        outfile.write_all(format!("let mut {} = {}.index(\
-                            cannolib::Value::Number(cannolib::NumericType::\
-                            Integer({})));\n", local, packed_values, ndx)
-                            .as_bytes()).unwrap();
-                        unpack_values(outfile, indent, Some(scope), &local,
-                            elt)?;
-                    },
-                    _ => unimplemented!()
-                }
+            let values_local = Local::new();
+            let starred: Vec<usize> = elts.iter().enumerate()
+                .filter(|&(_, elt)| match *elt {
+                    Expression::Starred { .. } => true,
+                    _ => false
+                }).map(|(ndx, _)| ndx).collect();
+
+            // `unpack` raises a `ValueError` when the number of values doesn't
+            // match, `unpack_starred` when there are too few values
+            outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+            match starred.len() {
+                0 => outfile.write_all(format!("let mut {} = {}.unpack({})\
+                    .into_iter();\n", values_local, packed_values, elts.len())
+                    .as_bytes()).unwrap(),
+                1 => outfile.write_all(format!("let mut {} = {}\
+                    .unpack_starred({}, {}).into_iter();\n", values_local,
+                    packed_values, starred[0], elts.len() - starred[0] - 1)
+                    .as_bytes()).unwrap(),
+                _ => return Err(CompilerError::SyntaxError("multiple starred \
+                    expressions in assignment".to_string()))
+            }
+
+            for elt in elts.iter() {
+                let local = Local::new();
+                let elt = match *elt {
+                    Expression::Starred { ref value, .. } => value,
+                    ref elt => elt
+                };
+
+                outfile.write(INDENT.repeat(indent).as_bytes()).unwrap();
+                outfile.write_all(format!("let mut {} = {}.next().unwrap();\n",
+                    local, values_local).as_bytes()).unwrap();
+                unpack_values(outfile, indent, class_scope, &local, elt)?;
             }
         },
-        _ => panic!("unable to unpack values")
+        Expression::Starred { .. } => {
+            return Err(CompilerError::SyntaxError("starred assignment target \
+                must be in a list or tuple".to_string()))
+        },
+        _ => return Err(CompilerError::SyntaxError("can't assign to \
+            expression".to_string()))
     }
     Ok(())
 }
@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+
+use super::errors::CompilerError;
+use ::parser::ast::*;
+
+/// Where a name assigned to in a scope is stored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    Local,
+    Global,
+    Nonlocal
+}
+
+/// The names bound by a function, class or comprehension. Every scope
+/// corresponds to one table pushed onto `cannoli_scope_list` at run time.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    is_function: bool,
+    is_class: bool,
+    declarations: HashMap<String, Binding>,
+    locals: HashSet<String>
+}
+
+impl Scope {
+    /// Analyzes a function or lambda body, parameters are always local
+    pub fn function(params: &Arguments, body: &Vec<Statement>)
+        -> Result<Scope, CompilerError> {
+        let mut scope = Scope::analyze(body)?;
+        scope.is_function = true;
+
+        let (args, vararg, kwonlyargs, kwarg) = match *params {
+            Arguments::Arguments { ref args, ref vararg, ref kwonlyargs,
+                ref kwarg, .. } => (args, vararg, kwonlyargs, kwarg)
+        };
+        let params = args.iter().chain(vararg.iter()).chain(kwonlyargs.iter())
+            .chain(kwarg.iter());
+
+        for param in params {
+            let name = match *param {
+                Arg::Arg { ref arg, .. } => arg
+            };
+
+            match scope.declarations.get(name) {
+                Some(&Binding::Global) =>
+                    return Err(CompilerError::SyntaxError(format!("name '{}' \
+                        is parameter and global", name))),
+                Some(&Binding::Nonlocal) =>
+                    return Err(CompilerError::SyntaxError(format!("name '{}' \
+                        is parameter and nonlocal", name))),
+                _ => ()
+            }
+            scope.locals.insert(name.clone());
+        }
+        Ok(scope)
+    }
+
+    /// Analyzes a class body, its names live in the class and aren't visible
+    /// to nested functions
+    pub fn class(body: &Vec<Statement>) -> Result<Scope, CompilerError> {
+        let mut scope = Scope::analyze(body)?;
+        scope.is_class = true;
+        Ok(scope)
+    }
+
+    /// Comprehension targets are always local to the comprehension
+    pub fn comprehension() -> Scope {
+        Scope {
+            is_function: false,
+            is_class: false,
+            declarations: HashMap::new(),
+            locals: HashSet::new()
+        }
+    }
+
+    pub fn is_function(&self) -> bool {
+        self.is_function
+    }
+
+    pub fn is_class(&self) -> bool {
+        self.is_class
+    }
+
+    pub fn binding(&self, name: &str) -> Binding {
+        match self.declarations.get(name) {
+            Some(binding) => *binding,
+            None => Binding::Local
+        }
+    }
+
+    /// Determines if the name is assigned to in this scope
+    pub fn binds(&self, name: &str) -> bool {
+        self.locals.contains(name)
+    }
+
+    /// Names that have to be bound by an enclosing function
+    pub fn nonlocals(&self) -> Vec<&String> {
+        self.declarations.iter()
+            .filter(|&(_, binding)| *binding == Binding::Nonlocal)
+            .map(|(name, _)| name).collect()
+    }
+
+    fn analyze(body: &Vec<Statement>) -> Result<Scope, CompilerError> {
+        let mut scope = Scope::comprehension();
+
+        scope.add_stmts(body)?;
+        for (name, _) in scope.declarations.iter() {
+            scope.locals.remove(name);
+        }
+        Ok(scope)
+    }
+
+    fn declare(&mut self, names: &Vec<String>, binding: Binding)
+        -> Result<(), CompilerError> {
+        for name in names.iter() {
+            match self.declarations.get(name) {
+                Some(other) if *other != binding =>
+                    return Err(CompilerError::SyntaxError(format!("name '{}' \
+                        is nonlocal and global", name))),
+                _ => ()
+            }
+            self.declarations.insert(name.clone(), binding);
+        }
+        Ok(())
+    }
+
+    // Nested function and class bodies are separate scopes, only their names
+    // are bound here
+    fn add_stmts(&mut self, stmts: &Vec<Statement>)
+        -> Result<(), CompilerError> {
+        for stmt in stmts.iter() {
+            match *stmt {
+                Statement::FunctionDef { ref name, .. } |
+                Statement::ClassDef { ref name, .. } => {
+                    self.locals.insert(name.clone());
+                },
+                Statement::Delete { ref targets } |
+                Statement::Assign { ref targets, .. } => {
+                    for target in targets.iter() {
+                        self.add_target(target);
+                    }
+                },
+                Statement::AugAssign { ref target, .. } |
+                Statement::AnnAssign { ref target, .. } => {
+                    self.add_target(target)
+                },
+                Statement::For { ref target, ref body, ref orelse, .. } => {
+                    self.add_target(target);
+                    self.add_stmts(body)?;
+                    self.add_stmts(orelse)?;
+                },
+                Statement::While { ref body, ref orelse, .. } |
+                Statement::If { ref body, ref orelse, .. } => {
+                    self.add_stmts(body)?;
+                    self.add_stmts(orelse)?;
+                },
+                Statement::With { ref items, ref body } => {
+                    for item in items.iter() {
+                        match *item {
+                            WithItem::WithItem {
+                                optional_vars: Some(ref target), .. } =>
+                                self.add_target(target),
+                            _ => ()
+                        }
+                    }
+                    self.add_stmts(body)?;
+                },
+                Statement::Try { ref body, ref handlers, ref orelse,
+                    ref finalbody } => {
+                    self.add_stmts(body)?;
+                    for handler in handlers.iter() {
+                        let (name, body) = match *handler {
+                            ExceptHandler::ExceptHandler { ref name,
+                                ref body, .. } => (name, body)
+                        };
+                        if let Some(ref name) = *name {
+                            self.locals.insert(name.clone());
+                        }
+                        self.add_stmts(body)?;
+                    }
+                    self.add_stmts(orelse)?;
+                    self.add_stmts(finalbody)?;
+                },
+                Statement::Import { ref names } |
+                Statement::ImportFrom { ref names, .. } => {
+                    for alias in names.iter() {
+                        let (name, asname) = match *alias {
+                            Alias::Alias { ref name, ref asname } =>
+                                (name, asname)
+                        };
+                        match *asname {
+                            Some(ref asname) =>
+                                self.locals.insert(asname.clone()),
+                            None => self.locals.insert(name.clone())
+                        };
+                    }
+                },
+                Statement::Global { ref names } =>
+                    self.declare(names, Binding::Global)?,
+                Statement::Nonlocal { ref names } =>
+                    self.declare(names, Binding::Nonlocal)?,
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    fn add_target(&mut self, target: &Expression) {
+        match *target {
+            Expression::Name { ref id, .. } => {
+                self.locals.insert(id.clone());
+            },
+            Expression::Starred { ref value, .. } => self.add_target(value),
+            Expression::List { ref elts, .. } |
+            Expression::Tuple { ref elts, .. } => {
+                for elt in elts.iter() {
+                    self.add_target(elt);
+                }
+            },
+            _ => ()
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use regex::Regex;
 
 use super::errors::CompilerError;
+use ::parser::ast::*;
 
 /// Returns the root directory of the given file and the file name sans ext
 pub fn get_file_prefix(file: &str) -> Result<(String, String), CompilerError> {
@@ -23,6 +24,258 @@ pub fn get_file_prefix(file: &str) -> Result<(String, String), CompilerError> {
     }
 }
 
+/// Escapes a string so it can be output between the quotes of a Rust string
+/// literal. Quotes, backslashes and control characters are escaped, all other
+/// Unicode characters are output as they are.
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"'  => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => escaped.push_str("\\0"),
+            c if c.is_control() =>
+                escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
+
+/// Escapes bytes so they can be output between the quotes of a Rust byte
+/// string literal, anything but printable ASCII is output as a hex escape.
+pub fn escape_bytes(bytes: &Vec<u8>) -> String {
+    let mut escaped = String::with_capacity(bytes.len());
+
+    for byte in bytes.iter() {
+        match *byte {
+            b'"'  => escaped.push_str("\\\""),
+            b'\\' => escaped.push_str("\\\\"),
+            byte if byte >= b' ' && byte <= b'~' =>
+                escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\x{:02x}", byte))
+        }
+    }
+    escaped
+}
+
+/// Converts the digits of a Python integer literal, without its radix prefix,
+/// into canonical decimal form. Literals may be arbitrarily large, so the
+/// value is accumulated in base 10^9 limbs, least significant first.
+pub fn canonical_integer(digits: &str, radix: u32)
+    -> Result<String, CompilerError> {
+    const LIMB_BASE: u64 = 1_000_000_000;
+    let mut limbs: Vec<u64> = vec![0];
+
+    for c in digits.chars() {
+        // The lexer accepts any Unicode decimal digit, Python only ASCII ones
+        let mut carry = match c.to_digit(radix) {
+            Some(digit) => digit as u64,
+            None => return Err(CompilerError::SyntaxError(format!(
+                "invalid digit '{}' in integer literal", c)))
+        };
+
+        for limb in limbs.iter_mut() {
+            let value = *limb * radix as u64 + carry;
+            *limb = value % LIMB_BASE;
+            carry = value / LIMB_BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+
+    let mut canonical = limbs.pop().unwrap().to_string();
+    for limb in limbs.iter().rev() {
+        canonical.push_str(&format!("{:09}", limb));
+    }
+    Ok(canonical)
+}
+
+/// Converts the digits of a Python float literal, such as `.5` or `1e999`,
+/// into an `f64` expression that is valid Rust.
+pub fn float_literal(digits: &str) -> Result<String, CompilerError> {
+    // The lexer accepts any Unicode decimal digit, Python only ASCII ones
+    let value: f64 = match digits.parse() {
+        Ok(value) => value,
+        Err(_) => return Err(CompilerError::SyntaxError(format!(
+            "invalid float literal '{}'", digits)))
+    };
+
+    if value.is_infinite() {
+        Ok("std::f64::INFINITY".to_string())
+    } else {
+        Ok(format!("{:?}", value))
+    }
+}
+
 lazy_static! {
    static ref FILENAME_RE: Regex = Regex::new(r"(.*/)?(.+)\.py$").unwrap();
 }
+
+/// Determines if a function body contains a `yield` expression, making it a
+/// generator function. Nested function and class bodies are not searched.
+pub fn stmts_contain_yield(stmts: &Vec<Statement>) -> bool {
+    stmts.iter().any(|stmt| stmt_contains_yield(stmt))
+}
+
+fn stmt_contains_yield(stmt: &Statement) -> bool {
+    match *stmt {
+        Statement::FunctionDef { ref args, ref decorator_list, .. } => {
+            exprs_contain_yield(decorator_list) || args_contain_yield(args)
+        },
+        Statement::ClassDef { ref bases, ref keywords, ref decorator_list,
+            .. } => {
+            exprs_contain_yield(bases) || exprs_contain_yield(decorator_list)
+                || keywords.iter().any(|keyword| match *keyword {
+                    Keyword::Keyword { ref value, .. } =>
+                        expr_contains_yield(value)
+                })
+        },
+        Statement::Return { ref value } => opt_contains_yield(value),
+        Statement::Delete { ref targets } => exprs_contain_yield(targets),
+        Statement::Assign { ref targets, ref value } => {
+            exprs_contain_yield(targets) || expr_contains_yield(value)
+        },
+        Statement::AugAssign { ref target, ref value, .. } => {
+            expr_contains_yield(target) || expr_contains_yield(value)
+        },
+        Statement::AnnAssign { ref target, ref annotation, ref value } => {
+            expr_contains_yield(target) || expr_contains_yield(annotation)
+                || opt_contains_yield(value)
+        },
+        Statement::For { ref target, ref iter, ref body, ref orelse } => {
+            expr_contains_yield(target) || expr_contains_yield(iter)
+                || stmts_contain_yield(body) || stmts_contain_yield(orelse)
+        },
+        Statement::While { ref test, ref body, ref orelse } |
+        Statement::If { ref test, ref body, ref orelse } => {
+            expr_contains_yield(test) || stmts_contain_yield(body)
+                || stmts_contain_yield(orelse)
+        },
+        Statement::With { ref items, ref body } => {
+            items.iter().any(|item| match *item {
+                WithItem::WithItem { ref context_expr, ref optional_vars } =>
+                    expr_contains_yield(context_expr)
+                        || opt_contains_yield(optional_vars)
+            }) || stmts_contain_yield(body)
+        },
+        Statement::Raise { ref exc, ref cause } => {
+            opt_contains_yield(exc) || opt_contains_yield(cause)
+        },
+        Statement::Try { ref body, ref handlers, ref orelse,
+            ref finalbody } => {
+            stmts_contain_yield(body) || stmts_contain_yield(orelse)
+                || stmts_contain_yield(finalbody)
+                || handlers.iter().any(|handler| match *handler {
+                    ExceptHandler::ExceptHandler { ref etype, ref body, .. } =>
+                        opt_contains_yield(etype) || stmts_contain_yield(body)
+                })
+        },
+        Statement::Assert { ref test, ref msg } => {
+            expr_contains_yield(test) || opt_contains_yield(msg)
+        },
+        Statement::Expr { ref value } => expr_contains_yield(value),
+        Statement::Import { .. } | Statement::ImportFrom { .. } |
+        Statement::Global { .. } | Statement::Nonlocal { .. } |
+        Statement::Pass | Statement::Break | Statement::Continue => false
+    }
+}
+
+/// Determines if an expression contains a `yield`, lambda bodies and the
+/// elements of comprehensions are not searched since they have their own
+/// scope.
+pub fn expr_contains_yield(expr: &Expression) -> bool {
+    match *expr {
+        Expression::Yield { .. } | Expression::YieldFrom { .. } => true,
+        Expression::BoolOp { ref values, .. } => exprs_contain_yield(values),
+        Expression::BinOp { ref left, ref right, .. } => {
+            expr_contains_yield(left) || expr_contains_yield(right)
+        },
+        Expression::UnaryOp { ref operand, .. } => expr_contains_yield(operand),
+        Expression::Lambda { ref args, .. } => args_contain_yield(args),
+        Expression::If { ref test, ref body, ref orelse } => {
+            expr_contains_yield(test) || expr_contains_yield(body)
+                || expr_contains_yield(orelse)
+        },
+        Expression::Dict { ref keys, ref values } => {
+            exprs_contain_yield(keys) || exprs_contain_yield(values)
+        },
+        Expression::Set { ref elts } | Expression::List { ref elts, .. } |
+        Expression::Tuple { ref elts, .. } => exprs_contain_yield(elts),
+        Expression::ListComp { ref generators, .. } |
+        Expression::SetComp { ref generators, .. } |
+        Expression::DictComp { ref generators, .. } |
+        Expression::Generator { ref generators, .. } => {
+            // Only the outermost iterable is evaluated in the enclosing scope
+            match generators.first() {
+                Some(&Comprehension::Comprehension { ref iter, .. }) =>
+                    expr_contains_yield(iter),
+                None => false
+            }
+        },
+        Expression::Compare { ref left, ref comparators, .. } => {
+            expr_contains_yield(left) || exprs_contain_yield(comparators)
+        },
+        Expression::Call { ref func, ref args, ref keywords } => {
+            expr_contains_yield(func) || exprs_contain_yield(args)
+                || keywords.iter().any(|keyword| match *keyword {
+                    Keyword::Keyword { ref value, .. } =>
+                        expr_contains_yield(value)
+                })
+        },
+        Expression::Attribute { ref value, .. } |
+        Expression::Starred { ref value, .. } => expr_contains_yield(value),
+        Expression::Subscript { ref value, ref slice, .. } => {
+            expr_contains_yield(value) || slice_contains_yield(slice)
+        },
+        Expression::FormattedValue { ref value, ref format_spec, .. } => {
+            expr_contains_yield(value) || match *format_spec {
+                Some(ref format_spec) => expr_contains_yield(format_spec),
+                None => false
+            }
+        },
+        Expression::JoinedStr { ref values } => exprs_contain_yield(values),
+        Expression::None | Expression::Num { .. } | Expression::Str { .. } |
+        Expression::Bytes { .. } |
+        Expression::NameConstant { .. } | Expression::Ellipsis |
+        Expression::Name { .. } => false
+    }
+}
+
+fn exprs_contain_yield(exprs: &Vec<Expression>) -> bool {
+    exprs.iter().any(|expr| expr_contains_yield(expr))
+}
+
+fn opt_contains_yield(expr: &Option<Expression>) -> bool {
+    match *expr {
+        Some(ref expr) => expr_contains_yield(expr),
+        None => false
+    }
+}
+
+fn slice_contains_yield(slice: &Slice) -> bool {
+    match *slice {
+        Slice::Slice { ref lower, ref upper, ref step } => {
+            opt_contains_yield(lower) || opt_contains_yield(upper)
+                || opt_contains_yield(step)
+        },
+        Slice::ExtSlice { ref dims } => {
+            dims.iter().any(|dim| slice_contains_yield(dim))
+        },
+        Slice::Index { ref value } => expr_contains_yield(value)
+    }
+}
+
+/// Default values are evaluated in the scope enclosing the function
+fn args_contain_yield(args: &Arguments) -> bool {
+    match *args {
+        Arguments::Arguments { ref kw_defaults, ref defaults, .. } => {
+            exprs_contain_yield(kw_defaults) || exprs_contain_yield(defaults)
+        }
+    }
+}
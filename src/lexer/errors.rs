@@ -14,6 +14,7 @@ pub enum LexerError
    MalformedNamedUnicodeEscape,
    UnknownUnicodeName(String),
    BytesNonASCII,
+   MalformedFormattedString(String),
    MissingDigits,
    MalformedFloat,
    MalformedImaginary,
@@ -50,6 +51,8 @@ impl fmt::Display for LexerError
             write!(f, "missing digits"),
          LexerError::BytesNonASCII =>
             write!(f, "bytes cannot contain non-ASCII characters"),
+         LexerError::MalformedFormattedString(ref s) =>
+            write!(f, "f-string: {}", s),
          LexerError::MalformedFloat =>
             write!(f, "malformed floating point number"),
          LexerError::MalformedImaginary =>
@@ -82,6 +85,7 @@ impl error::Error for LexerError
          LexerError::UnknownUnicodeName(_) => "unknown unicode name",
          LexerError::BytesNonASCII =>
             "bytes cannot contain non-ASCII characters",
+         LexerError::MalformedFormattedString(_) => "malformed f-string",
          LexerError::MissingDigits => "missing digits",
          LexerError::MalformedFloat => "malformed floating point number",
          LexerError::MalformedImaginary => "malformed imaginary number",
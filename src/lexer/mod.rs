@@ -11,7 +11,7 @@ use std::cmp;
 use std::iter::Peekable;
 use unicode_names;
 
-use self::tokens::{Token, keyword_lookup, symbol_lookup};
+use self::tokens::{Token, FormatPart, keyword_lookup, symbol_lookup};
 use self::errors::LexerError;
 
 
@@ -28,11 +28,19 @@ impl <'a> Lexer<'a>
 {
    pub fn new(input: &str)
       -> Lexer
+   {
+      Lexer::starting_at(input, 1)
+   }
+
+   /// Lexes `input` as if it began on `line_number` of an enclosing source,
+   /// used for the replacement fields of formatted strings.
+   pub fn starting_at(input: &str, line_number: usize)
+      -> Lexer
    {
       Lexer{lexer:
          StringJoiningLexer::new(
             BytesJoiningLexer::new(
-               InternalLexer::new(input)
+               InternalLexer::starting_at(input, line_number)
             )
          ).peekable()}
    }
@@ -63,13 +71,14 @@ impl <'a> StringJoiningLexer<'a>
    }
 
    fn string_follows(&mut self)
-      -> Option<String>
+      -> Option<Token>
    {
       match self.lexer.peek()
       {
-         Some(&(_, Ok(Token::String(_)))) =>
+         Some(&(_, Ok(Token::String(_)))) |
+         Some(&(_, Ok(Token::FormattedString(_)))) =>
          {
-            Some(self.lexer.next().unwrap().1.unwrap().lexeme())
+            Some(self.lexer.next().unwrap().1.unwrap())
          },
          _ => None,
       }
@@ -85,14 +94,15 @@ impl <'a> Iterator for StringJoiningLexer<'a>
    {
       match self.lexer.next()
       {
-         Some((line_number, Ok(Token::String(s)))) =>
+         Some((line_number, Ok(token @ Token::String(_)))) |
+         Some((line_number, Ok(token @ Token::FormattedString(_)))) =>
          {
-            let mut token_str = s.clone();
+            let mut token = token;
             while let Some(follow) = self.string_follows()
             {
-               token_str.push_str(&follow)
+               token = join_strings(token, follow);
             }
-            Some((line_number, Ok(Token::String(token_str))))
+            Some((line_number, Ok(token)))
          },
          result => result,
       }
@@ -178,11 +188,17 @@ impl <'a> InternalLexer<'a>
 {
    pub fn new(input: &str)
       -> InternalLexer
+   {
+      InternalLexer::starting_at(input, 1)
+   }
+
+   pub fn starting_at(input: &str, line_number: usize)
+      -> InternalLexer
    {
       InternalLexer{indent_stack: vec![0],
          dedent_count: 0,
          text: input,
-         line_number: 1,
+         line_number,
          line_start: true,
          open_braces: 0,
       }
@@ -302,8 +318,9 @@ impl <'a> InternalLexer<'a>
    {
       let (_, end) = STRING_PREFIX_RE.find(self.text).unwrap();
       let caps = STRING_PREFIX_RE.captures(self.text).unwrap();
-      let raw = caps.at(1).is_some();
-      let quote = caps.at(2).unwrap();
+      let raw = caps.at(2).is_some() || caps.at(3).is_some();
+      let formatted = caps.at(1).is_some() || caps.at(4).is_some();
+      let quote = caps.at(5).unwrap();
 
       self.update_text(end);
 
@@ -311,6 +328,10 @@ impl <'a> InternalLexer<'a>
 
       match re.find(self.text)
       {
+         Some((_, end)) if formatted =>
+         {
+            self.build_formatted_contents(end, re, raw)
+         },
          Some((_, end)) =>
          {
             self.build_string_contents(end, re, raw)
@@ -333,25 +354,31 @@ impl <'a> InternalLexer<'a>
       self.update_text(end);
       self.line_number += newlines;
 
-      let expanded =
-         if !raw
-         {
-            if let Some(err) = check_escape_errors(contents)
-            {
-               return (current_line_number, Err(err))
-            }
-            // check_escape_errors also iterates over structurally valid
-            // named unicode characters - duplicating some of
-            // the iteration done below in replace_all - this is
-            // kept separate for code clarity, but could be merged
-            ESCAPES_RE.replace_all(contents, |caps: &Captures|
-               process_escape_sequence(caps.at(1).unwrap_or("")))
-         }
-         else
-         {
-            contents.to_owned()
-         };
-      (current_line_number, Ok(Token::String(expanded)))
+      match expand_string_contents(contents, raw)
+      {
+         Ok(expanded) => (current_line_number, Ok(Token::String(expanded))),
+         Err(err) => (current_line_number, Err(err)),
+      }
+   }
+
+   fn build_formatted_contents(&mut self, end: usize, re: &Regex, raw: bool)
+      -> (usize, ResultToken)
+   {
+      let caps = re.captures(self.text).unwrap();
+      let contents = caps.at(1).unwrap_or("");
+      let newlines = NEWLINE_RE.find_iter(&contents).count();
+
+      let current_line_number = self.line_number;
+      self.update_text(end);
+      self.line_number += newlines;
+
+      let chars: Vec<char> = contents.chars().collect();
+      match split_format_parts(&chars, raw, false)
+      {
+         Ok((parts, _)) =>
+            (current_line_number, Ok(Token::FormattedString(parts))),
+         Err(err) => (current_line_number, Err(err)),
+      }
    }
 
    fn build_bytes_contents(&mut self, end: usize, re: &Regex, raw: bool)
@@ -405,8 +432,6 @@ impl <'a> InternalLexer<'a>
       let raw = caps.at(1).is_some() || caps.at(2).is_some();
       let quote = caps.at(3).unwrap();
 
-      println!("is raw: {} '{}' '{}'", raw, caps.at(1).unwrap_or(""),
-         caps.at(2).unwrap_or(""));
       self.update_text(end);
 
       let (re, fail, err) = determine_string_processing(quote);
@@ -596,6 +621,224 @@ fn replace_string_bytes<F>(re: &Regex, contents: &str, process: F)
    bytes
 }
 
+fn expand_string_contents(contents: &str, raw: bool)
+   -> Result<String, LexerError>
+{
+   if raw
+   {
+      return Ok(contents.to_owned());
+   }
+
+   if let Some(err) = check_escape_errors(contents)
+   {
+      return Err(err);
+   }
+   // check_escape_errors also iterates over structurally valid
+   // named unicode characters - duplicating some of
+   // the iteration done below in replace_all - this is
+   // kept separate for code clarity, but could be merged
+   Ok(ESCAPES_RE.replace_all(contents, |caps: &Captures|
+      process_escape_sequence(caps.at(1).unwrap_or(""))))
+}
+
+// An f-string anywhere in a run of adjacent string literals makes the whole
+// run an f-string
+fn join_strings(left: Token, right: Token)
+   -> Token
+{
+   match (left, right)
+   {
+      (Token::String(mut s), Token::String(follow)) =>
+      {
+         s.push_str(&follow);
+         Token::String(s)
+      },
+      (left, right) =>
+      {
+         let mut parts = vec![];
+         for token in vec![left, right]
+         {
+            match token
+            {
+               Token::String(s) =>
+                  push_format_part(&mut parts, FormatPart::Literal(s)),
+               Token::FormattedString(follow) =>
+               {
+                  for part in follow
+                  {
+                     push_format_part(&mut parts, part);
+                  }
+               },
+               _ => unreachable!(),
+            }
+         }
+         Token::FormattedString(parts)
+      },
+   }
+}
+
+fn push_format_part(parts: &mut Vec<FormatPart>, part: FormatPart)
+{
+   if let FormatPart::Literal(ref s) = part
+   {
+      if s.is_empty()
+      {
+         return;
+      }
+      if let Some(&mut FormatPart::Literal(ref mut last)) = parts.last_mut()
+      {
+         last.push_str(s);
+         return;
+      }
+   }
+   parts.push(part);
+}
+
+/// Splits the contents of an f-string into literals and replacement fields.
+/// Within a format spec, an unmatched '}' ends the parts and its position is
+/// returned.
+fn split_format_parts(chars: &[char], raw: bool, in_spec: bool)
+   -> Result<(Vec<FormatPart>, usize), LexerError>
+{
+   let mut parts = vec![];
+   let mut literal = String::new();
+   let mut pos = 0;
+
+   while pos < chars.len()
+   {
+      match chars[pos]
+      {
+         '\\' if !raw =>
+         {
+            // named unicode escapes are the only escapes containing braces
+            literal.push('\\');
+            pos += 1;
+            if chars[pos..].starts_with(&['N', '{'])
+            {
+               while pos < chars.len()
+               {
+                  literal.push(chars[pos]);
+                  pos += 1;
+                  if chars[pos - 1] == '}'
+                  {
+                     break;
+                  }
+               }
+            }
+            else if pos < chars.len() && chars[pos] != '{' && chars[pos] != '}'
+            {
+               literal.push(chars[pos]);
+               pos += 1;
+            }
+         },
+         c @ '{' | c @ '}' if !in_spec && chars.get(pos + 1) == Some(&c) =>
+         {
+            literal.push(c);
+            pos += 2;
+         },
+         '{' =>
+         {
+            let expanded = expand_string_contents(&literal, raw)?;
+            push_format_part(&mut parts, FormatPart::Literal(expanded));
+            literal.clear();
+
+            let (field, len) = split_format_field(&chars[pos + 1..], raw)?;
+            parts.push(field);
+            pos += len + 1;
+         },
+         '}' if in_spec => break,
+         '}' => return Err(format_error("single '}' is not allowed")),
+         c =>
+         {
+            literal.push(c);
+            pos += 1;
+         },
+      }
+   }
+
+   let expanded = expand_string_contents(&literal, raw)?;
+   push_format_part(&mut parts, FormatPart::Literal(expanded));
+   Ok((parts, pos))
+}
+
+/// Splits a replacement field following its '{', returning the field and the
+/// number of characters up to and including its '}'
+fn split_format_field(chars: &[char], raw: bool)
+   -> Result<(FormatPart, usize), LexerError>
+{
+   let mut depth = 0;
+   let mut quote = None;
+   let mut pos = 0;
+
+   while pos < chars.len()
+   {
+      let c = chars[pos];
+      if c == '\\'
+      {
+         return Err(format_error("expression part cannot include a \
+            backslash"));
+      }
+
+      match quote
+      {
+         Some(q) => if c == q { quote = None },
+         None =>
+         {
+            match c
+            {
+               '\'' | '"' => quote = Some(c),
+               '(' | '[' | '{' => depth += 1,
+               ')' | ']' | '}' if depth > 0 => depth -= 1,
+               '#' => return Err(format_error("expression part cannot \
+                  include '#'")),
+               '!' if depth == 0 && chars.get(pos + 1) != Some(&'=') => break,
+               '}' | ':' if depth == 0 => break,
+               _ => (),
+            }
+         },
+      }
+      pos += 1;
+   }
+
+   let expr: String = chars[..pos].iter().collect();
+   if expr.trim().is_empty() && pos < chars.len()
+   {
+      return Err(format_error("empty expression not allowed"));
+   }
+
+   let mut conversion = None;
+   if chars.get(pos) == Some(&'!')
+   {
+      match chars.get(pos + 1)
+      {
+         Some(&c) if c == 's' || c == 'r' || c == 'a' => conversion = Some(c),
+         _ => return Err(format_error("invalid conversion character: \
+            expected 's', 'r', or 'a'")),
+      }
+      pos += 2;
+   }
+
+   let mut spec = vec![];
+   if chars.get(pos) == Some(&':')
+   {
+      let (parts, len) = split_format_parts(&chars[pos + 1..], raw, true)?;
+      spec = parts;
+      pos += len + 1;
+   }
+
+   match chars.get(pos)
+   {
+      Some(&'}') => Ok((FormatPart::Field{expr, conversion, spec}, pos + 1)),
+      _ => Err(format_error("expecting '}'")),
+   }
+}
+
+fn format_error(message: &str)
+   -> LexerError
+{
+   LexerError::MalformedFormattedString(message.to_owned())
+}
+
 fn determine_string_processing(quote: &str)
    -> (&Regex, &Regex, LexerError)
 {
@@ -894,9 +1137,10 @@ lazy_static!
        )
       ").unwrap();
    static ref STRING_START_RE : Regex =
-      Regex::new(r#"^(?:[uU]|[rR])?['"]"#).unwrap();
+      Regex::new(r#"^(?:[uU]|[fF][rR]?|[rR][fF]?)?['"]"#).unwrap();
    static ref STRING_PREFIX_RE : Regex =
-      Regex::new(r#"^(?:[uU]|([rR]))?('''|'|"""|")"#).unwrap();
+      Regex::new(r#"^(?:[uU]|([fF])([rR])?|([rR])([fF])?)?('''|'|"""|")"#)
+      .unwrap();
    static ref STRING_SINGLE_QUOTE_RE : Regex =
       Regex::new(r#"^(?s)((?:\\\r\n|\\.|[^\\\r\n'])*)'"#).unwrap();
    static ref STRING_DOUBLE_QUOTE_RE : Regex =
@@ -955,7 +1199,7 @@ lazy_static!      // Recursion limit reached above o.O
 mod tests
 {
    use super::Lexer;
-   use super::tokens::Token;
+   use super::tokens::{Token, FormatPart};
    use super::errors::LexerError;
 
    #[test]
@@ -1515,6 +1759,102 @@ mod tests
       assert_eq!(l.next(), Some((2, Ok(Token::Newline))));
    }
 
+   #[test]
+   fn test_byte_strings_13()
+   {
+      let chars = "b'\\xff\\x41'";
+      let mut l = Lexer::new(chars);
+      let token = l.next().unwrap().1.unwrap();
+      assert_eq!(token, Token::Bytes(vec![255, 65]));
+      assert_eq!(token.lexeme(), "\u{fffd}A");
+   }
+
+   #[test]
+   fn test_formatted_strings_1()
+   {
+      let chars = "f'a{x}b' F\"{{}}\" rf'\\n{y!r}' fR''\n";
+      let mut l = Lexer::new(chars);
+      assert_eq!(l.next(), Some((1, Ok(Token::FormattedString(vec![
+         FormatPart::Literal("a".to_owned()),
+         FormatPart::Field{expr: "x".to_owned(), conversion: None,
+            spec: vec![]},
+         FormatPart::Literal("b{}\\n".to_owned()),
+         FormatPart::Field{expr: "y".to_owned(), conversion: Some('r'),
+            spec: vec![]},
+      ])))));
+      assert_eq!(l.next(), Some((1, Ok(Token::Newline))));
+   }
+
+   #[test]
+   fn test_formatted_strings_2()
+   {
+      let chars = "f'{x:>{width}.2f}\\t{d[\"k\"] != {1: 2}}'\n";
+      let mut l = Lexer::new(chars);
+      assert_eq!(l.next(), Some((1, Ok(Token::FormattedString(vec![
+         FormatPart::Field{expr: "x".to_owned(), conversion: None,
+            spec: vec![
+               FormatPart::Literal(">".to_owned()),
+               FormatPart::Field{expr: "width".to_owned(), conversion: None,
+                  spec: vec![]},
+               FormatPart::Literal(".2f".to_owned()),
+            ]},
+         FormatPart::Literal("\t".to_owned()),
+         FormatPart::Field{expr: "d[\"k\"] != {1: 2}".to_owned(),
+            conversion: None, spec: vec![]},
+      ])))));
+      assert_eq!(l.next(), Some((1, Ok(Token::Newline))));
+   }
+
+   #[test]
+   fn test_formatted_strings_3()
+   {
+      let chars = "'a' f'{b}' 'c'\n'd' f''\nf'\\N{BULLET} {e!s:}'\n";
+      let mut l = Lexer::new(chars);
+      assert_eq!(l.next(), Some((1, Ok(Token::FormattedString(vec![
+         FormatPart::Literal("a".to_owned()),
+         FormatPart::Field{expr: "b".to_owned(), conversion: None,
+            spec: vec![]},
+         FormatPart::Literal("c".to_owned()),
+      ])))));
+      assert_eq!(l.next(), Some((1, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((2, Ok(Token::FormattedString(vec![
+         FormatPart::Literal("d".to_owned()),
+      ])))));
+      assert_eq!(l.next(), Some((2, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((3, Ok(Token::FormattedString(vec![
+         FormatPart::Literal("\u{2022} ".to_owned()),
+         FormatPart::Field{expr: "e".to_owned(), conversion: Some('s'),
+            spec: vec![]},
+      ])))));
+      assert_eq!(l.next(), Some((3, Ok(Token::Newline))));
+   }
+
+   #[test]
+   fn test_formatted_strings_4()
+   {
+      let chars = "f'{x'\nf'}'\nf'{}'\nf'{x!z}'\nf'{a\\n}'\nf'{a#}'\n";
+      let mut l = Lexer::new(chars);
+      assert_eq!(l.next(), Some((1, Err(LexerError::MalformedFormattedString(
+         "expecting '}'".to_owned())))));
+      assert_eq!(l.next(), Some((1, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((2, Err(LexerError::MalformedFormattedString(
+         "single '}' is not allowed".to_owned())))));
+      assert_eq!(l.next(), Some((2, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((3, Err(LexerError::MalformedFormattedString(
+         "empty expression not allowed".to_owned())))));
+      assert_eq!(l.next(), Some((3, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((4, Err(LexerError::MalformedFormattedString(
+         "invalid conversion character: expected 's', 'r', or 'a'"
+            .to_owned())))));
+      assert_eq!(l.next(), Some((4, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((5, Err(LexerError::MalformedFormattedString(
+         "expression part cannot include a backslash".to_owned())))));
+      assert_eq!(l.next(), Some((5, Ok(Token::Newline))));
+      assert_eq!(l.next(), Some((6, Err(LexerError::MalformedFormattedString(
+         "expression part cannot include '#'".to_owned())))));
+      assert_eq!(l.next(), Some((6, Ok(Token::Newline))));
+   }
+
    #[test]
    fn test_implicit_1()
    {
@@ -93,6 +93,7 @@ pub enum Token
    Identifier(String),
    String(String),
    Bytes(Vec<u8>),
+   FormattedString(Vec<FormatPart>),
    DecInteger(String),
    BinInteger(String),
    OctInteger(String),
@@ -101,6 +102,20 @@ pub enum Token
    Imaginary(String),
 }
 
+/// A piece of an f-string, replacement field expressions are kept as source
+/// text for the parser
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub enum FormatPart
+{
+   Literal(String),
+   Field
+   {
+      expr: String,
+      conversion: Option<char>,
+      spec: Vec<FormatPart>,
+   },
+}
+
 impl Token
 {
    pub fn is_decimal_integer(&self)
@@ -132,7 +147,8 @@ impl Token
             Token::DecInteger(s) | Token::BinInteger(s) |
             Token::OctInteger(s) | Token::HexInteger(s) |
             Token::Float(s) | Token::Imaginary(s) => s,
-         Token::Bytes(s) => String::from_utf8(s).unwrap(),
+         Token::Bytes(s) => String::from_utf8_lossy(&s).into_owned(),
+         Token::FormattedString(parts) => format_parts_lexeme(parts),
          _ =>
          {
             match LEXEMES.get(&self)
@@ -170,6 +186,38 @@ impl Token
    }
 }
 
+fn format_parts_lexeme(parts: Vec<FormatPart>)
+   -> String
+{
+   let mut lexeme = String::new();
+
+   for part in parts
+   {
+      match part
+      {
+         FormatPart::Literal(s) =>
+            lexeme.push_str(&s.replace("{", "{{").replace("}", "}}")),
+         FormatPart::Field{expr, conversion, spec} =>
+         {
+            lexeme.push('{');
+            lexeme.push_str(&expr);
+            if let Some(c) = conversion
+            {
+               lexeme.push('!');
+               lexeme.push(c);
+            }
+            if !spec.is_empty()
+            {
+               lexeme.push(':');
+               lexeme.push_str(&format_parts_lexeme(spec));
+            }
+            lexeme.push('}');
+         },
+      }
+   }
+   lexeme
+}
+
 pub fn keyword_lookup(token_str: &str)
    -> Token
 {
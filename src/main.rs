@@ -22,7 +22,9 @@ fn main() {
         .arg(Arg::with_name("o")
             .short("o")
             .takes_value(true)
-            .help("Sets the optimization level: [1-3]"))
+            .possible_values(&["1", "2", "3"])
+            .help("Sets the optimization level: [1-3], asserts are stripped \
+                at every level"))
         .arg(Arg::with_name("parse")
             .long("parse")
             .help("Only parses the input file and prints the AST"))
@@ -61,6 +61,10 @@ pub enum Expression {
         keywords: Vec<Keyword> },
     Num { n: Number },
     Str { s: String },
+    FormattedValue { value: Box<Expression>, conversion: Option<Conversion>,
+        format_spec: Option<Box<Expression>> },
+    JoinedStr { values: Vec<Expression> },
+    Bytes { s: Vec<u8> },
     NameConstant { value: Singleton },
     Ellipsis,
     Attribute { value: Box<Expression>, attr: String, ctx: ExprContext },
@@ -181,6 +185,13 @@ pub enum Singleton {
     False
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub enum Conversion {
+    Str,
+    Repr,
+    Ascii
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Number {
     DecInteger(String),
@@ -1,6 +1,7 @@
 use std::error;
 use std::fmt;
 
+use ::lexer::errors::LexerError;
 use ::lexer::tokens::Token;
 use super::OptToken;
 
@@ -13,6 +14,8 @@ pub enum ParserError
     PositionalArgAfterKeyword(OptToken),
     KeywordExpression(OptToken),
     ExpectedEOF(OptToken),
+    MixedBytes(OptToken),
+    LexerError(LexerError, usize),
     UnexpectedEOF
 }
 
@@ -45,6 +48,13 @@ impl fmt::Display for ParserError {
                     opt.clone().unwrap().1.unwrap().lexeme(),
                     opt.clone().unwrap().0)
             },
+            ParserError::MixedBytes(ref opt) => {
+                write!(f, "cannot mix bytes and nonbytes literals (line {})",
+                    opt.clone().unwrap().0)
+            },
+            ParserError::LexerError(ref err, line) => {
+                write!(f, "{} (line {})", err, line)
+            },
             ParserError::UnexpectedEOF => write!(f, "unexpected EOF")
         }
     }
@@ -61,6 +71,9 @@ impl error::Error for ParserError {
                 "positional argument follows keywork argument unpacking",
             ParserError::KeywordExpression(_) => "keyword can't be expression",
             ParserError::ExpectedEOF(_) => "expected EOF",
+            ParserError::MixedBytes(_) =>
+                "cannot mix bytes and nonbytes literals",
+            ParserError::LexerError(..) => "lexer error",
             ParserError::UnexpectedEOF => "unexpected EOF"
         }
     }
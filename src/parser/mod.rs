@@ -3,7 +3,7 @@ mod util;
 mod errors;
 
 use super::lexer::{Lexer, ResultToken};
-use super::lexer::tokens::Token;
+use super::lexer::tokens::{Token, FormatPart};
 use self::ast::*;
 use self::util::{ArgType, TLCompType};
 use self::errors::ParserError;
@@ -1551,7 +1551,32 @@ fn parse_atom(opt: OptToken, stream: &mut Lexer)
             Ok((stream.next(), Expression::Num { n: Number::Float(n) })),
         Token::Imaginary(n) =>
             Ok((stream.next(), Expression::Num { n: Number::Imaginary(n) })),
-        Token::String(s) => Ok((stream.next(), Expression::Str { s })),
+        Token::String(s) => {
+            // Adjacent literals are joined by the lexer, unless they're mixed
+            let opt = stream.next();
+            match util::get_token(&opt) {
+                Ok(Token::Bytes(_)) => Err(ParserError::MixedBytes(opt)),
+                _ => Ok((opt, Expression::Str { s }))
+            }
+        },
+        Token::Bytes(s) => {
+            let opt = stream.next();
+            match util::get_token(&opt) {
+                Ok(Token::String(_)) | Ok(Token::FormattedString(_)) =>
+                    Err(ParserError::MixedBytes(opt)),
+                _ => Ok((opt, Expression::Bytes { s }))
+            }
+        },
+        Token::FormattedString(parts) => {
+            let line = opt.clone().unwrap().0;
+            let opt = stream.next();
+            match util::get_token(&opt) {
+                Ok(Token::Bytes(_)) => Err(ParserError::MixedBytes(opt)),
+                _ => Ok((opt, Expression::JoinedStr {
+                    values: parse_format_parts(parts, line)?
+                }))
+            }
+        },
         Token::Ellipsis => Ok((stream.next(), Expression::Ellipsis)),
         Token::None => Ok((stream.next(),
                 Expression::NameConstant { value: Singleton::None })),
@@ -1563,6 +1588,57 @@ fn parse_atom(opt: OptToken, stream: &mut Lexer)
     }
 }
 
+fn parse_format_parts(parts: Vec<FormatPart>, line: usize)
+    -> Result<Vec<Expression>, ParserError> {
+    let mut values = vec![];
+
+    for part in parts {
+        let value = match part {
+            FormatPart::Literal(s) => Expression::Str { s },
+            FormatPart::Field { expr, conversion, spec } => {
+                let conversion = match conversion {
+                    Some('s') => Some(Conversion::Str),
+                    Some('r') => Some(Conversion::Repr),
+                    Some('a') => Some(Conversion::Ascii),
+                    _ => None
+                };
+                let format_spec = if spec.is_empty() {
+                    None
+                } else {
+                    Some(Box::new(Expression::JoinedStr {
+                        values: parse_format_parts(spec, line)?
+                    }))
+                };
+
+                Expression::FormattedValue {
+                    value: Box::new(parse_format_expr(&expr, line)?),
+                    conversion, format_spec
+                }
+            }
+        };
+        values.push(value);
+    }
+    Ok(values)
+}
+
+// Replacement fields are parsed as if they were parenthesized, this allows
+// them to span lines and to be bare tuples. Their tokens are numbered from
+// the line of the f-string so errors point into the original source.
+fn parse_format_expr(expr: &str, line: usize)
+    -> Result<Expression, ParserError> {
+    let source = format!("({})", expr);
+    let mut stream = Lexer::starting_at(&source, line);
+    let (opt, value) = parse_atom(stream.next(), &mut stream)?;
+
+    match opt {
+        Some(_) => {
+            util::get_token(&opt)?;
+            Err(ParserError::ExpectedEOF(opt))
+        },
+        None    => Ok(value)
+    }
+}
+
 fn parse_test_list_comp(opt: OptToken, ctype: TLCompType, stream: &mut Lexer)
     -> Result<(OptToken, Expression), ParserError> {
     let (opt, expr) = match util::get_token(&opt)? {
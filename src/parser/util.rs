@@ -9,8 +9,10 @@ pub fn get_token(opt: &Option<(usize, ResultToken)>)
     if opt.is_none() {
         Err(ParserError::UnexpectedEOF)
     } else {
-        let (_, result_token) = opt.clone().unwrap();
-        Ok(result_token.clone().unwrap())
+        match opt.clone().unwrap() {
+            (_, Ok(token)) => Ok(token),
+            (line, Err(err)) => Err(ParserError::LexerError(err, line))
+        }
     }
 }
 
@@ -20,7 +22,7 @@ fn get_some_token(opt: &Option<(usize, ResultToken)>) -> Option<Token> {
         None
     } else {
         let (_, result_token) = opt.clone().unwrap();
-        Some(result_token.clone().unwrap())
+        result_token.ok()
     }
 }
 
@@ -112,6 +114,7 @@ pub fn valid_simple_stmt(token: &Token) -> bool {
         Token::Assert   => true,
         Token::Import   => true,
         Token::From     => true,
+        Token::Times    => true, // star_expr in a testlist_star_expr
         _ if valid_flow_stmt(token) => true,
         _ => valid_test_expr(token)
     }
@@ -193,6 +196,8 @@ pub fn valid_expr(token: &Token) -> bool {
         Token::Float(_)      => true,
         Token::Imaginary(_)  => true,
         Token::String(_)     => true,
+        Token::Bytes(_)      => true,
+        Token::FormattedString(_) => true,
         Token::Ellipsis      => true,
         Token::None          => true,
         Token::True          => true,
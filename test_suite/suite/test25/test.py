@@ -0,0 +1,41 @@
+for x in [1, 2, 3, 4, 5, 6]:
+   if x == 2:
+      continue
+   if x == 5:
+      break
+   print(x)
+
+i = 0
+while i < 10:
+   i += 1
+   if i % 2 == 0:
+      continue
+   if i > 7:
+      break
+   print(i)
+else:
+   print("not printed")
+
+for x in [1, 2, 3]:
+   for y in [10, 20, 30]:
+      if y == 20:
+         break
+      print(x, y)
+   if x == 2:
+      continue
+   print("outer", x)
+
+a = 0
+while True:
+   a += 1
+   if a == 3:
+      break
+print(a)
+
+def find(items, val):
+   for item in items:
+      if item == val:
+         return True
+   return False
+
+print(find([1, 2, 3], 2), find([1, 2, 3], 5))
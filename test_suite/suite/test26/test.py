@@ -0,0 +1,50 @@
+for x in [1, 2, 3]:
+   print(x)
+else:
+   print("for else ran")
+
+for x in [1, 2, 3]:
+   if x == 2:
+      break
+else:
+   print("not printed")
+
+for x in []:
+   print("empty")
+else:
+   print("empty for else ran")
+
+class Counter:
+   def __init__(self):
+      self.calls = 0
+
+counter = Counter()
+
+def check(n):
+   counter.calls = counter.calls + 1
+   return n < 3
+
+a = 0
+while check(a):
+   a += 1
+else:
+   print("while else ran", a, counter.calls)
+
+a = 0
+while check(a):
+   if a == 1:
+      break
+   a += 1
+else:
+   print("not printed")
+print(a)
+
+for x in [1, 2]:
+   for y in [3, 4]:
+      print(x, y)
+   else:
+      if x == 1:
+         continue
+      break
+   print("not printed")
+print("done")
@@ -0,0 +1,74 @@
+def divide(a, b):
+    if b == 0:
+        raise ValueError("cannot divide by zero")
+    return a / b
+
+try:
+    print(divide(10, 2))
+    print(divide(1, 0))
+    print("not printed")
+except ValueError as e:
+    print("caught:", e)
+else:
+    print("not printed")
+finally:
+    print("finally ran")
+
+try:
+    print(divide(9, 3))
+except ValueError:
+    print("not printed")
+else:
+    print("else ran")
+
+def nested():
+    try:
+        try:
+            raise KeyError("inner")
+        except ValueError:
+            print("not printed")
+        finally:
+            print("inner finally")
+    except KeyError:
+        print("outer caught KeyError")
+        return "returned from handler"
+    finally:
+        print("outer finally")
+    return "not returned"
+
+print(nested())
+
+def reraise():
+    try:
+        raise TypeError("bad type")
+    except TypeError:
+        print("handling and re-raising")
+        raise
+
+try:
+    reraise()
+except (ValueError, TypeError) as e:
+    print("caught re-raised:", e)
+
+for i in [1, 2, 3, 4]:
+    try:
+        if i == 2:
+            continue
+        if i == 4:
+            break
+        print("loop", i)
+    finally:
+        print("loop finally", i)
+
+def finally_return():
+    try:
+        return "try"
+    finally:
+        print("cleanup before return")
+
+print(finally_return())
+
+try:
+    raise RuntimeError("catch all")
+except:
+    print("bare except")
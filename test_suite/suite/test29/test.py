@@ -0,0 +1,52 @@
+class Animal:
+    kind = "animal"
+
+    def __init__(self, name):
+        self.name = name
+
+    def speak(self):
+        return self.name + " makes a sound"
+
+    def describe(self):
+        return self.name + " is an " + self.kind
+
+class Dog(Animal):
+    def __init__(self, name, breed):
+        super().__init__(name)
+        self.breed = breed
+
+    def speak(self):
+        return self.name + " barks"
+
+d = Dog("Rex", "lab")
+print(d.speak())
+print(d.describe())
+print(d.breed)
+print(isinstance(d, Dog), isinstance(d, Animal), issubclass(Dog, Animal))
+print(issubclass(Animal, Dog))
+
+class A:
+    def who(self):
+        return "A"
+
+class B(A):
+    def who(self):
+        return "B" + super().who()
+
+class C(A):
+    def who(self):
+        return "C" + super().who()
+
+class D(B, C):
+    def who(self):
+        return "D" + super().who()
+
+print(D().who())
+print([cls.__name__ for cls in D.__mro__])
+
+class Outer:
+    class Inner:
+        value = 5
+    after = 10
+
+print(Outer.Inner.value, Outer.after)
@@ -0,0 +1,58 @@
+def shout(func):
+    def wrapper(x):
+        return func(x) + "!"
+    return wrapper
+
+def twice(func):
+    def wrapper(x):
+        return func(func(x))
+    return wrapper
+
+def tag(label):
+    print("creating tag", label)
+    def decorate(func):
+        def wrapper(x):
+            return "<" + label + ">" + func(x)
+        return wrapper
+    return decorate
+
+@shout
+def greet(name):
+    return "hello " + name
+
+print(greet("jon"))
+
+@tag("b")
+@shout
+def bold(text):
+    return text
+
+print(bold("hi"))
+
+@shout
+@twice
+def double(text):
+    return text + text
+
+print(double("ab"))
+
+def register(cls):
+    print("registering", cls.__name__)
+    cls.registered = True
+    return cls
+
+@register
+class Plugin:
+    pass
+
+print(Plugin.registered)
+
+class Shape:
+    def __init__(self, sides):
+        self.sides = sides
+
+    @staticmethod
+    def info():
+        return "shapes have sides"
+
+print(Shape.info())
@@ -0,0 +1,61 @@
+def make_tag():
+    print("evaluating default")
+    return "div"
+
+def element(content, tag=make_tag(), cls=None):
+    if cls == None:
+        return "<" + tag + ">" + content
+    return "<" + tag + " class=" + cls + ">" + content
+
+print(element("a"))
+print(element("b", "span"))
+print(element("c", cls="big"))
+print(element(tag="p", content="d"))
+
+def total(first, *rest):
+    result = first
+    for value in rest:
+        result = result + value
+    return result, rest
+
+print(total(1))
+print(total(1, 2, 3))
+
+def config(name, *, debug=False, level):
+    return name, debug, level
+
+print(config("app", level=3))
+print(config("app", level=1, debug=True))
+
+def collect(a, b=2, *args, c, d=4, **kwargs):
+    print(a, b, args, c, d, sorted(kwargs.items()))
+
+collect(1, c=3)
+collect(1, 5, 6, 7, c=8, d=9, e=10, f=11)
+
+def append_to(item, items=[]):
+    items.append(item)
+    return items
+
+print(append_to(1))
+print(append_to(2))
+
+try:
+    element()
+except TypeError:
+    print("missing argument raised TypeError")
+
+try:
+    element("x", colour="red")
+except TypeError:
+    print("unexpected keyword raised TypeError")
+
+try:
+    element("x", content="y")
+except TypeError:
+    print("multiple values raised TypeError")
+
+try:
+    total()
+except TypeError:
+    print("missing first raised TypeError")
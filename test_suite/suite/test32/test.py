@@ -0,0 +1,32 @@
+def show(a, b, c=0, d=0):
+    print(a, b, c, d)
+
+items = [1, 2, 3]
+print(*items)
+print("start", *items, "end")
+show(*items)
+show(*(5, 6), d=7)
+show(1, *[2], *[3, 4])
+
+def forward(*args, **kwargs):
+    show(*args, **kwargs)
+
+forward(1, 2, c=3, d=4)
+forward(1, b=2)
+
+def options(**kwargs):
+    return kwargs
+
+opts = options(c=10, d=20)
+show(1, 2, **opts)
+show(1, **options(b=2), **options(d=4))
+
+try:
+    show(1, 2, c=3, **opts)
+except TypeError:
+    print("duplicate keyword raised TypeError")
+
+try:
+    show(1, **options(b=2), **options(b=3))
+except TypeError:
+    print("duplicate mapping keyword raised TypeError")
@@ -0,0 +1,23 @@
+class Person:
+    def __init__(self, name, age):
+        self.name = name
+        self.age = age
+
+people = [Person("ann", 31), Person("bob", 25), Person("cat", 40)]
+print([p.name for p in sorted(people, key=lambda p: p.age)])
+
+square = lambda x: x * x
+print(square(7))
+
+add = lambda a, b=10: a + b
+print(add(1), add(1, 2))
+
+def make_multiplier(n):
+    return lambda x: x * n
+
+triple = make_multiplier(3)
+print(triple(5))
+
+print((lambda: "no args")())
+print(list(map(lambda x: x + 1, [1, 2, 3])))
+print((lambda *args, **kwargs: len(args) + len(kwargs))(1, 2, c=3))
@@ -0,0 +1,33 @@
+config = {"name": "cannoli", "version": 2, "debug": False}
+print(config)
+print(config["name"], config["version"])
+
+empty = {}
+print(empty, len(empty))
+
+defaults = {"debug": True, "level": 1}
+merged = {**defaults, **config, "level": 3}
+print(merged)
+
+ordered = {"z": 1, "a": 2, "m": 3, "a": 4}
+print(list(ordered.keys()))
+
+primes = {2, 3, 5, 7, 3, 2}
+print(sorted(primes), len(primes))
+print(3 in primes, 4 in primes)
+
+more = {*[1, 2], *(2, 3), 4}
+print(sorted(more))
+
+squares = {x: x * x for x in range(5)}
+print(squares)
+
+words = ["apple", "banana", "avocado", "cherry"]
+by_letter = {w[0]: w for w in words if len(w) > 5}
+print(by_letter)
+
+lengths = {len(w) for w in words}
+print(sorted(lengths))
+
+pairs = {(x, y) for x in range(3) for y in range(3) if x < y}
+print(sorted(pairs))
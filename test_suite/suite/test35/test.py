@@ -0,0 +1,45 @@
+def countdown(n):
+    while n > 0:
+        yield n
+        n -= 1
+
+for i in countdown(3):
+    print(i)
+
+gen = countdown(2)
+print(next(gen))
+print(next(gen))
+print(next(gen, "done"))
+
+def squares(xs):
+    for x in xs:
+        yield x * x
+
+print(list(squares([1, 2, 3])))
+print(sum(x * x for x in [1, 2, 3, 4]))
+print(list(x for x in range(10) if x % 3 == 0))
+
+def inner():
+    yield 1
+    yield 2
+    return "inner done"
+
+def outer():
+    result = yield from inner()
+    print(result)
+    yield from [3, 4]
+
+print(list(outer()))
+
+def echo():
+    received = yield "ready"
+    while received != None:
+        received = yield received
+
+e = echo()
+print(next(e))
+print(e.send("hello"))
+print(e.send("world"))
+
+naturals = (lambda: (yield 1))()
+print(next(naturals))
@@ -0,0 +1,67 @@
+count = 0
+
+def increment():
+    global count
+    count += 1
+
+increment()
+increment()
+print(count)
+
+def make_counter():
+    total = 0
+    def counter():
+        nonlocal total
+        total += 1
+        return total
+    return counter
+
+c = make_counter()
+c()
+c()
+print(c())
+
+def outer():
+    x = "outer"
+    def middle():
+        def inner():
+            nonlocal x
+            x = "inner"
+        inner()
+    middle()
+    return x
+
+print(outer())
+
+def shadow():
+    count = 100
+    def reset():
+        global count
+        count = 0
+        return count
+    reset()
+    return count
+
+print(shadow(), count)
+
+def memoize():
+    cache = {}
+    calls = 0
+    def fib(n):
+        nonlocal calls
+        calls += 1
+        if n in cache:
+            return cache[n]
+        result = n if n < 2 else fib(n - 1) + fib(n - 2)
+        cache.update({n: result})
+        return result
+    print(fib(20), calls)
+
+memoize()
+
+def define_global():
+    global created, other
+    created, other = "created", "other"
+
+define_global()
+print(created, other)
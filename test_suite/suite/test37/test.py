@@ -0,0 +1,58 @@
+class Manager:
+    def __init__(self, name, suppress=False):
+        self.name = name
+        self.suppress = suppress
+
+    def __enter__(self):
+        print("enter", self.name)
+        return self
+
+    def __exit__(self, exc_type, exc_value, traceback):
+        print("exit", self.name, exc_type == None)
+        return self.suppress
+
+class Pair:
+    def __enter__(self):
+        return (1, 2)
+
+    def __exit__(self, exc_type, exc_value, traceback):
+        print("pair closed")
+
+with Manager("a") as m:
+    print("body", m.name)
+
+with Manager("outer"), Manager("inner") as inner:
+    print("nested", inner.name)
+
+with Pair() as (x, y):
+    print(x + y)
+
+with Manager("suppressing", True):
+    raise ValueError("ignored")
+print("after suppressed")
+
+try:
+    with Manager("propagating"):
+        raise KeyError("passed on")
+except KeyError:
+    print("caught")
+
+def early_return():
+    with Manager("returning"):
+        return "returned"
+    print("unreachable")
+
+print(early_return())
+
+for i in [1, 2, 3]:
+    with Manager("loop" + str(i)):
+        if i == 1:
+            continue
+        if i == 2:
+            break
+print("loop done")
+
+with open("suite/test37/sample.txt") as f:
+    for line in f:
+        print(line.strip())
+print(f.closed)
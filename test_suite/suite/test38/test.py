@@ -0,0 +1,25 @@
+def check(n):
+    assert n > 0, "n must be positive, got " + str(n)
+    return n * 2
+
+print(check(4))
+
+try:
+    check(-1)
+except AssertionError as e:
+    print("caught:", e)
+
+try:
+    assert [], "empty lists are false"
+except AssertionError as e:
+    print("caught:", e)
+
+try:
+    assert False
+except AssertionError:
+    print("caught bare assert")
+
+calls = []
+assert len(calls) == 0, calls.append("message evaluated")
+print(calls)
+print(__debug__)
@@ -0,0 +1,50 @@
+x = 10
+print(x)
+del x
+try:
+    print(x)
+except NameError:
+    print("x is gone")
+
+class Box:
+    pass
+
+b = Box()
+b.label = "box"
+b.size = 3
+del b.label
+print(hasattr(b, "label"), b.size)
+
+d = {"a": 1, "b": 2, "c": 3}
+del d["b"]
+print(d)
+
+nums = [0, 1, 2, 3, 4, 5, 6]
+del nums[0]
+print(nums)
+del nums[1:3]
+print(nums)
+del nums[::2]
+print(nums)
+
+first, second = 1, 2
+del first, second
+try:
+    print(first)
+except NameError:
+    print("first is gone")
+
+def local_del():
+    y = "local"
+    del y
+    try:
+        y
+    except NameError:
+        print("y is gone")
+
+local_del()
+
+try:
+    del undefined
+except NameError:
+    print("nothing to delete")
@@ -0,0 +1,60 @@
+d = {}
+d["key"] = "value"
+d[1] = d["key"] * 2
+print(d)
+
+nums = [0, 1, 2, 3, 4, 5]
+nums[0] = 10
+nums[-1] = 50
+print(nums)
+nums[1:3] = ["a", "b", "c"]
+print(nums)
+nums[::2] = [None, None, None, None]
+print(nums)
+
+[a, b] = "xy"
+print(a, b)
+
+first, *rest = [1, 2, 3, 4]
+print(first, rest)
+
+*init, last = (1, 2, 3)
+print(init, last)
+
+head, *middle, tail = "ab"
+print(head, middle, tail)
+
+(p, [q, r]), s = (1, [2, 3]), 4
+print(p, q, r, s)
+
+class Point:
+    pass
+
+pt = Point()
+pt.x, pt.y = 3, 4
+print(pt.x, pt.y)
+
+grid = [[0, 0], [0, 0]]
+grid[0][1], grid[1][0] = "top", "bottom"
+print(grid)
+
+a = b = [1, 2]
+print(a, b)
+
+for key, (low, high) in [("r", (0, 1)), ("s", (2, 3))]:
+    print(key, low, high)
+
+try:
+    x, y = [1, 2, 3]
+except ValueError as e:
+    print("ValueError:", e)
+
+try:
+    x, y, z = [1, 2]
+except ValueError as e:
+    print("ValueError:", e)
+
+try:
+    x, y, *z = [1]
+except ValueError as e:
+    print("ValueError:", e)
@@ -0,0 +1,63 @@
+class Counter:
+    total = 0
+    total += 10
+
+    def __init__(self):
+        self.count = 0
+
+    def bump(self):
+        self.count += 1
+        return self
+
+c = Counter()
+c.bump().bump()
+print(c.count, Counter.total)
+
+hist = {}
+for word in "the cat and the hat".split():
+    hist[word] = hist.get(word, 0)
+    hist[word] += 1
+print(hist)
+
+nums = [1, 2, 3, 4]
+nums[0] -= 5
+nums[-1] *= 10
+nums[1:3] += [7]
+print(nums)
+
+evaluations = []
+
+def key(k):
+    evaluations.append(k)
+    return k
+
+table = {"a": 1}
+table[key("a")] += 1
+print(table, evaluations)
+
+class Vector:
+    def __init__(self, items):
+        self.items = items
+
+    def __iadd__(self, other):
+        print("in place add")
+        self.items.extend(other.items)
+        return self
+
+    def __add__(self, other):
+        print("binary add")
+        return Vector(self.items + other.items)
+
+v = Vector([1])
+original = v
+v += Vector([2])
+print(v.items, v == original)
+
+shared = [1]
+alias = shared
+shared += [2]
+print(alias)
+
+s = "abc"
+s += "def"
+print(s)
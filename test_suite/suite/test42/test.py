@@ -0,0 +1,29 @@
+x = None
+print(x is None, x is not None)
+
+a = [1, 2]
+b = a
+c = [1, 2]
+print(a is b, a is c, a == c, a is not c)
+
+def find(items, target):
+    for item in items:
+        if item == target:
+            return item
+    return None
+
+result = find([1, 2, 3], 4)
+if result is None:
+    print("not found")
+
+print(+5, +-3, -+2.5)
+
+class Matrix:
+    def __init__(self, rows):
+        self.rows = rows
+
+    def __pos__(self):
+        return "positive matrix"
+
+m = Matrix([[1, 2], [3, 4]])
+print(+m)
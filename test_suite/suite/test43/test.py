@@ -0,0 +1,27 @@
+calls = []
+
+def value(n):
+    calls.append(n)
+    return n
+
+print(1 < value(2) < 3)
+print(calls)
+
+calls = []
+print(value(3) < value(2) < value(1))
+print(calls)
+
+calls = []
+print(value(1) <= value(1) == value(1) != value(2))
+print(calls)
+
+x = 5
+print(0 < x < 10, 0 < x > 10, 1 == 1 < 2)
+print(1 in [1, 2] not in [[1]])
+
+if 0 <= x < 3:
+    print("low")
+elif 3 <= x < 7:
+    print("middle")
+else:
+    print("high")
@@ -0,0 +1,22 @@
+class Item:
+    def __init__(self, ok):
+        self.ok = ok
+
+items = [Item(True), None, Item(False), None, Item(True)]
+print(len([x for x in items if x is not None if x.ok]))
+print(len({x for x in items if x is not None if x.ok}))
+print(sorted({i: x.ok for i, x in enumerate(items) if x is not None if x.ok}))
+print(sum(1 for x in items if x is not None if x.ok))
+
+checked = []
+
+def check(name, result):
+    checked.append(name)
+    return result
+
+print([n for n in range(4) if check("a" + str(n), n % 2 == 0)
+       if check("b" + str(n), n > 0)])
+print(checked)
+
+pairs = [(a, b) for a in range(3) if a > 0 for b in range(3) if b != a]
+print(pairs)
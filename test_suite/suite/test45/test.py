@@ -0,0 +1,20 @@
+print("double \"quotes\" and 'single'")
+print('single \'quotes\' and "double"')
+print("back\\slash", len("back\\slash"))
+print("line one\nline two\ttabbed")
+print("braces {} and {0} and {{}}")
+print("unicode: héllo wörld ✓ é \N{GREEK SMALL LETTER ALPHA}")
+print("bell \a and null \0 and escape \x1b end", len("\a\0\x1b"))
+print("\"); std::process::exit(1); (\"")
+print("""triple "quoted" string
+spanning lines""")
+
+class Weird:
+    pass
+
+w = Weird()
+setattr(w, "a\"b", 1)
+print(getattr(w, "a\"b"))
+
+d = {"key\"with\\escapes": "value\n"}
+print(d)
@@ -0,0 +1,12 @@
+data = b'abc' b"\x00\n\xff"
+print(data)
+print(len(data))
+print(data[0], data[-1])
+print(data[1:3])
+
+for byte in b'hi':
+    print(byte)
+
+print(b'abc' == b'abc')
+print(b'abc' == 'abc')
+print(b'"quoted" \\ path')
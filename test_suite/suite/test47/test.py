@@ -0,0 +1,21 @@
+name = 'world'
+width = 8
+pi = 3.14159
+items = [1, 2, 3]
+
+print(f'hello {name}!')
+print(F"{name!r} has {len(name)} letters")
+print(f'{pi:.2f} {pi:>{width}.3f}|')
+print(f'{{literal}} {items[0] + items[-1]}')
+print(f'{name!s:^11}|')
+print('joined ' f'{width * 2}' " strings")
+print(rf'\d+ {name}')
+print(f'''multi
+{name.upper()
+}''')
+
+def describe(value):
+    return f'{type(value).__name__}: {value}'
+
+print(describe(42))
+print(f"{'nested'}")
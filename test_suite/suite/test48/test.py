@@ -0,0 +1,13 @@
+z = 3 + 4j
+print(z)
+print(abs(z))
+print(z.real, z.imag)
+print(z * 1j)
+print(z - 2.5)
+print(z / 2)
+print(z == 3 + 4J)
+print(repr(.5j), repr(2.j), repr(1e3j), repr(-0j))
+
+signal = [1j ** n for n in range(4)]
+print(signal)
+print(sum([1 + 1j, 2 - 3j]))
@@ -0,0 +1,11 @@
+print(0X1F, 0x1f, 0O17, 0o17, 0B101, 0b101, 000, 0)
+
+# Literals at the edges of 32 bit integers
+print(2147483647, -2147483647, -2147483647 - 1)
+print(0x7FFFFFFF, 0o17777777777, 0b1111111111111111111111111111111)
+print(0x7fffffff - 2147483647, 2147483646 + 1)
+
+h = 0
+for c in 'cannoli':
+    h = (h * 31 + ord(c)) & 0xFFFFFF
+print(h)
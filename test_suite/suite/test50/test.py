@@ -0,0 +1,18 @@
+print(-7 % 3, 7 % -3, -7 % -3, 7 % 3)
+print(-7 // 3, 7 // -3, -7.5 // 2)
+print(7 / 2, 6 / 3, -1 / 4)
+print(-7.5 % 2, 7 % 2.5)
+print(2 ** -1, 2 ** 10, (-2) ** 3, 4 ** 0.5)
+print(1 + 2.0, 3 * 0.5, 10 - True)
+
+x = 17
+x %= -5
+print(x)
+x /= 2
+print(x)
+
+for op in [lambda: 1 / 0, lambda: 1 % 0, lambda: 1 // 0, lambda: 0.0 ** -1]:
+    try:
+        op()
+    except ZeroDivisionError as e:
+        print('ZeroDivisionError:', e)
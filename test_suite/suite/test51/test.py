@@ -0,0 +1,49 @@
+def returns_from_handler():
+    try:
+        raise ValueError('returned')
+    except ValueError as e:
+        return str(e)
+
+print(returns_from_handler())
+
+def handler_name_after_return():
+    e = 'outer'
+    for attempt in range(2):
+        try:
+            raise KeyError(attempt)
+        except KeyError as e:
+            if attempt == 0:
+                continue
+            break
+    try:
+        print(e)
+    except NameError:
+        print('e is unbound after break')
+
+handler_name_after_return()
+
+def reraises():
+    try:
+        raise ValueError('first')
+    except ValueError as err:
+        raise TypeError('second')
+
+try:
+    reraises()
+except TypeError as exc:
+    print('caught', exc)
+
+try:
+    print(exc)
+except NameError:
+    print('exc is unbound')
+
+while True:
+    try:
+        1 / 0
+    except ZeroDivisionError as zero:
+        break
+try:
+    print(zero)
+except NameError:
+    print('zero is unbound')
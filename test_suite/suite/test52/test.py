@@ -0,0 +1,17 @@
+source = [1, 2, 3]
+doubled = (x * 2 for x in source)
+source = [10, 20, 30]
+print(list(doubled))
+
+def numbers():
+    print('numbers called')
+    return [4, 5]
+
+gen = (n + m for n in numbers() for m in source)
+print('generator created')
+print(list(gen))
+
+try:
+    bad = (x for x in 42)
+except TypeError:
+    print('TypeError at creation')
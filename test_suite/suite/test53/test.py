@@ -0,0 +1,42 @@
+y = 'global y'
+
+def delete_local():
+    y = 'local y'
+    print(y)
+    del y
+    try:
+        print(y)
+    except UnboundLocalError as e:
+        print('raised', type(e).__name__)
+
+delete_local()
+
+def read_before_assignment():
+    try:
+        print(y)
+    except NameError as e:
+        print('NameError subclass:', type(e).__name__)
+    y = 'assigned later'
+    return y
+
+print(read_before_assignment())
+
+def delete_twice():
+    z = 1
+    del z
+    try:
+        del z
+    except UnboundLocalError:
+        print('cannot delete z twice')
+
+delete_twice()
+
+def reads_global():
+    return y
+
+print(reads_global())
+del y
+try:
+    print(y)
+except NameError as e:
+    print('NameError:', e)
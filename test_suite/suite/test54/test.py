@@ -0,0 +1,31 @@
+class Manager:
+    def __enter__(self):
+        return 'entered'
+
+    def __exit__(self, exc_type, exc_value, traceback):
+        return False
+
+class Example:
+    total = 0
+    for i in range(4):
+        total += i
+
+    with Manager() as state:
+        inside = state
+
+    try:
+        raise ValueError('caught')
+    except ValueError as err:
+        message = str(err)
+
+    def show(self):
+        return (self.total, self.i, self.state, self.inside, self.message)
+
+print(Example.total, Example.i, Example.state)
+print(Example().show())
+print(hasattr(Example, 'err'))
+
+try:
+    print(i)
+except NameError:
+    print('i is not a global')
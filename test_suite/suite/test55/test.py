@@ -0,0 +1,36 @@
+def load():
+    global greet
+    from helpers import greet
+
+def local_only():
+    from helpers import LIMIT as limit
+    return limit * 2
+
+load()
+print(greet('module'))
+print(local_only())
+
+try:
+    print(limit)
+except NameError:
+    print('limit stayed local')
+
+class Config:
+    from helpers import LIMIT
+
+    def limit(self):
+        return self.LIMIT
+
+print(Config.LIMIT, Config().limit())
+
+def outer():
+    value = None
+
+    def inner():
+        nonlocal value
+        from helpers import LIMIT as value
+
+    inner()
+    return value
+
+print(outer())
@@ -0,0 +1,8 @@
+print(.5, 1., 1.e3, 2.5E-3, 0.000001)
+print(1e300 * 10, -1e999, 1e999 == 1e999 * 2)
+print(10.5, 00.25, 3.14159, 0e0)
+values = [.25, 1.e-2, 6.02e23]
+total = 0.0
+for value in values:
+    total = total + value
+print(total)
@@ -1136,6 +1136,35 @@ fn assignment() {
         ]
     };
     assert_eq!(ast, expected);
+
+    let stream = Lexer::new("*a, b = c\n");
+    let ast = parser::parse_start_symbol(stream).unwrap();
+
+    let expected = Ast::Module {
+        body: vec![
+            Statement::Assign {
+                targets: vec![
+                    Expression::Tuple {
+                        elts: vec![
+                            Expression::Starred {
+                                value: Box::new(Expression::Name {
+                                    id: String::from("a"),
+                                    ctx: ExprContext::Load
+                                }),
+                                ctx: ExprContext::Load
+                            },
+                            Expression::Name { id: String::from("b"),
+                                ctx: ExprContext::Load }
+                        ],
+                        ctx: ExprContext::Load
+                    }
+                ],
+                value: Expression::Name { id: String::from("c"),
+                    ctx: ExprContext::Load }
+            }
+        ]
+    };
+    assert_eq!(ast, expected);
 }
 
 #[test]
@@ -2378,3 +2407,145 @@ fn comparison_ops() {
     };
     assert_eq!(ast, expected);
 }
+
+#[test]
+fn bytes_literals() {
+    let stream = Lexer::new("b'ab' b\"c\\x00\"\n");
+    let ast = parser::parse_start_symbol(stream).unwrap();
+
+    let expected = Ast::Module {
+        body: vec![
+            Statement::Expr {
+                value: Expression::Bytes { s: vec![97, 98, 99, 0] }
+            }
+        ]
+    };
+    assert_eq!(ast, expected);
+
+    let stream = Lexer::new("x = b'abc'[1]\n");
+    let ast = parser::parse_start_symbol(stream).unwrap();
+
+    let expected = Ast::Module {
+        body: vec![
+            Statement::Assign {
+                targets: vec![
+                    Expression::Name { id: String::from("x"),
+                        ctx: ExprContext::Load }
+                ],
+                value: Expression::Subscript {
+                    value: Box::new(Expression::Bytes { s: vec![97, 98, 99] }),
+                    slice: Box::new(Slice::Index {
+                        value: Expression::Num {
+                            n: Number::DecInteger(String::from("1"))
+                        }
+                    }),
+                    ctx: ExprContext::Load
+                }
+            }
+        ]
+    };
+    assert_eq!(ast, expected);
+
+    let stream = Lexer::new("'abc' b'def'\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+
+    let stream = Lexer::new("b'abc' 'def'\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+}
+
+#[test]
+fn formatted_strings() {
+    let stream = Lexer::new("f'a{b!r:>{c}}' 'd'\n");
+    let ast = parser::parse_start_symbol(stream).unwrap();
+
+    let expected = Ast::Module {
+        body: vec![
+            Statement::Expr {
+                value: Expression::JoinedStr {
+                    values: vec![
+                        Expression::Str { s: String::from("a") },
+                        Expression::FormattedValue {
+                            value: Box::new(Expression::Name {
+                                id: String::from("b"), ctx: ExprContext::Load
+                            }),
+                            conversion: Some(Conversion::Repr),
+                            format_spec: Some(Box::new(Expression::JoinedStr {
+                                values: vec![
+                                    Expression::Str { s: String::from(">") },
+                                    Expression::FormattedValue {
+                                        value: Box::new(Expression::Name {
+                                            id: String::from("c"),
+                                            ctx: ExprContext::Load
+                                        }),
+                                        conversion: None,
+                                        format_spec: None
+                                    }
+                                ]
+                            }))
+                        },
+                        Expression::Str { s: String::from("d") }
+                    ]
+                }
+            }
+        ]
+    };
+    assert_eq!(ast, expected);
+
+    let stream = Lexer::new("f'{x, y}'\n");
+    let ast = parser::parse_start_symbol(stream).unwrap();
+
+    let expected = Ast::Module {
+        body: vec![
+            Statement::Expr {
+                value: Expression::JoinedStr {
+                    values: vec![
+                        Expression::FormattedValue {
+                            value: Box::new(Expression::Tuple {
+                                elts: vec![
+                                    Expression::Name { id: String::from("x"),
+                                        ctx: ExprContext::Load },
+                                    Expression::Name { id: String::from("y"),
+                                        ctx: ExprContext::Load }
+                                ],
+                                ctx: ExprContext::Load
+                            }),
+                            conversion: None,
+                            format_spec: None
+                        }
+                    ]
+                }
+            }
+        ]
+    };
+    assert_eq!(ast, expected);
+
+    let stream = Lexer::new("f'{a b}'\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+
+    let stream = Lexer::new("f'{a}' b'c'\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+}
+
+#[test]
+fn malformed_formatted_strings() {
+    let stream = Lexer::new("f\"{x}}\"\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+
+    let stream = Lexer::new("f\"{\"\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+
+    let stream = Lexer::new("f\"{x:{{}}\"\n");
+    assert!(parser::parse_start_symbol(stream).is_err());
+
+    let stream = Lexer::new("x = 1\nf\"{x}}\"\n");
+    let err = parser::parse_start_symbol(stream).unwrap_err();
+    assert!(err.to_string().ends_with("(line 2)"));
+
+    let stream = Lexer::new("x = 1\nf'{a b}'\n");
+    let err = parser::parse_start_symbol(stream).unwrap_err();
+    assert!(err.to_string().ends_with("(line 2)"));
+
+    let stream = Lexer::new("x = 1\ny = f'''\n{a b}'''\n");
+    let err = parser::parse_start_symbol(stream).unwrap_err();
+    assert!(err.to_string().ends_with("(line 2)"));
+}